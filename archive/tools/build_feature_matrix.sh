@@ -5,7 +5,7 @@ root_dir="$(git rev-parse --show-toplevel)"
 target_dir="$root_dir/target/release"
 output_root="$root_dir/archive/binaries/feature-matrix"
 
-all_features="pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,futility,reverse-futility,razoring,probcut,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,see"
+all_features="pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,lmp,futility,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,capture-history,history-gravity,see"
 
 baseline_names=(
   base
@@ -14,13 +14,18 @@ baseline_names=(
   add-check-extensions
   add-null-move
   add-lmr
+  add-lmp
   add-futility
   add-reverse-futility
   add-razoring
   add-probcut
+  add-correction-history
   add-tt-cutoffs
   add-killer-moves
   add-history-heuristic
+  add-continuation-history
+  add-capture-history
+  add-history-gravity
   add-see
   add-iid
   add-singular-extensions
@@ -34,13 +39,18 @@ baseline_features=(
   "check-extensions"
   "null-move"
   "lmr"
+  "lmp"
   "futility"
   "reverse-futility"
   "razoring"
   "probcut"
+  "correction-history"
   "tt-cutoffs"
   "killer-moves"
   "history-heuristic"
+  "continuation-history"
+  "see,capture-history"
+  "history-heuristic,history-gravity"
   "see"
   "tt-cutoffs,iid"
   "tt-cutoffs,singular-extensions"
@@ -56,35 +66,45 @@ full_names=(
   sub-check-extensions
   sub-null-move
   sub-lmr
+  sub-lmp
   sub-futility
   sub-reverse-futility
   sub-razoring
   sub-probcut
+  sub-correction-history
   sub-tt-move-ordering
   sub-killer-moves
   sub-history-heuristic
-  sub-see
+  sub-continuation-history
+  sub-capture-history
+  sub-history-gravity
+  sub-see-stack
   sub-tt-stack
 )
 
 full_features=(
   "$all_features"
-  "aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,futility,reverse-futility,razoring,probcut,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,see"
-  "pvs,iid,singular-extensions,check-extensions,null-move,lmr,futility,reverse-futility,razoring,probcut,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,see"
-  "pvs,aspiration-windows,singular-extensions,check-extensions,null-move,lmr,futility,reverse-futility,razoring,probcut,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,see"
-  "pvs,aspiration-windows,iid,check-extensions,null-move,lmr,futility,reverse-futility,razoring,probcut,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,see"
-  "pvs,aspiration-windows,iid,singular-extensions,null-move,lmr,futility,reverse-futility,razoring,probcut,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,see"
-  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,lmr,futility,reverse-futility,razoring,probcut,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,see"
-  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,futility,reverse-futility,razoring,probcut,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,see"
-  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,reverse-futility,razoring,probcut,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,see"
-  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,futility,razoring,probcut,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,see"
-  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,futility,reverse-futility,probcut,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,see"
-  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,futility,reverse-futility,razoring,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,see"
-  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,futility,reverse-futility,razoring,probcut,tt-cutoffs,killer-moves,history-heuristic,see"
-  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,futility,reverse-futility,razoring,probcut,tt-cutoffs,tt-move-ordering,history-heuristic,see"
-  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,futility,reverse-futility,razoring,probcut,tt-cutoffs,tt-move-ordering,killer-moves,see"
-  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,futility,reverse-futility,razoring,probcut,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic"
-  "pvs,aspiration-windows,check-extensions,null-move,lmr,futility,reverse-futility,razoring,probcut,killer-moves,history-heuristic,see"
+  "aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,lmp,futility,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,capture-history,history-gravity,see"
+  "pvs,iid,singular-extensions,check-extensions,null-move,lmr,lmp,futility,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,capture-history,history-gravity,see"
+  "pvs,aspiration-windows,singular-extensions,check-extensions,null-move,lmr,lmp,futility,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,capture-history,history-gravity,see"
+  "pvs,aspiration-windows,iid,check-extensions,null-move,lmr,lmp,futility,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,capture-history,history-gravity,see"
+  "pvs,aspiration-windows,iid,singular-extensions,null-move,lmr,lmp,futility,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,capture-history,history-gravity,see"
+  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,lmr,lmp,futility,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,capture-history,history-gravity,see"
+  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmp,futility,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,capture-history,history-gravity,see"
+  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,futility,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,capture-history,history-gravity,see"
+  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,lmp,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,capture-history,history-gravity,see"
+  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,lmp,futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,capture-history,history-gravity,see"
+  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,lmp,futility,reverse-futility,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,capture-history,history-gravity,see"
+  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,lmp,futility,reverse-futility,razoring,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,capture-history,history-gravity,see"
+  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,lmp,futility,reverse-futility,razoring,probcut,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,capture-history,history-gravity,see"
+  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,lmp,futility,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,killer-moves,history-heuristic,continuation-history,capture-history,history-gravity,see"
+  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,lmp,futility,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,history-heuristic,continuation-history,capture-history,history-gravity,see"
+  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,lmp,futility,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,continuation-history,capture-history,history-gravity,see"
+  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,lmp,futility,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,capture-history,history-gravity,see"
+  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,lmp,futility,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,history-gravity,see"
+  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,lmp,futility,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,capture-history,see"
+  "pvs,aspiration-windows,iid,singular-extensions,check-extensions,null-move,lmr,lmp,futility,reverse-futility,razoring,probcut,correction-history,tt-cutoffs,tt-move-ordering,killer-moves,history-heuristic,continuation-history,history-gravity"
+  "pvs,aspiration-windows,check-extensions,null-move,lmr,lmp,futility,reverse-futility,razoring,probcut,correction-history,killer-moves,history-heuristic,continuation-history,capture-history,history-gravity,see"
 )
 
 build_variant() {
@@ -1,7 +1,6 @@
-use crate::search::init_lmr;
-use crate::search::search;
+use crate::search::{init_lmr, search, search_with_eval, SearchLimits};
 use crate::tpt::TranspositionTable;
-use crate::Position;
+use crate::{PestoProvider, Position};
 use std::thread;
 use std::time::Instant;
 
@@ -11,6 +10,34 @@ struct BenchPos {
     depth: u8,
 }
 
+const BENCH_POSITIONS: [BenchPos; 5] = [
+    BenchPos {
+        name: "Start Position",
+        fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        depth: 14,
+    },
+    BenchPos {
+        name: "KiwiPete (Tricky)",
+        fen: "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        depth: 14,
+    },
+    BenchPos {
+        name: "Middlegame (Giuoco Piano)",
+        fen: "r4rk1/1pp1qppp/p1np1n2/4p3/2BPP1b1/2P2N2/P1P2PPP/R1BQR1K1 w - - 0 1",
+        depth: 14,
+    },
+    BenchPos {
+        name: "Endgame (Rook+Pawn)",
+        fen: "8/8/2p5/4k3/1r6/8/2R2K2/8 w - - 0 1",
+        depth: 16,
+    },
+    BenchPos {
+        name: "Tactical (WAC-2)",
+        fen: "2rr3k/pp3pp1/1nnqbN1p/3p4/2pP4/2P3Q1/PPB2PPP/R1B1R1K1 w - - 0 1",
+        depth: 13,
+    },
+];
+
 const BENCH_STACK_SIZE_BYTES: usize = 32 * 1024 * 1024;
 
 fn run_with_large_stack<F>(f: F)
@@ -31,65 +58,33 @@ fn run_benchmark_suite() {
     run_with_large_stack(|| {
         init_lmr();
 
-        let positions = [
-            BenchPos {
-                name: "Start Position",
-                fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
-                depth: 14,
-            },
-            BenchPos {
-                name: "KiwiPete (Tricky)",
-                fen: "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
-                depth: 14,
-            },
-            BenchPos {
-                name: "Middlegame (Giuoco Piano)",
-                fen: "r4rk1/1pp1qppp/p1np1n2/4p3/2BPP1b1/2P2N2/P1P2PPP/R1BQR1K1 w - - 0 1",
-                depth: 14,
-            },
-            BenchPos {
-                name: "Endgame (Rook+Pawn)",
-                fen: "8/8/2p5/4k3/1r6/8/2R2K2/8 w - - 0 1",
-                depth: 16,
-            },
-            BenchPos {
-                name: "Tactical (WAC-2)",
-                fen: "2rr3k/pp3pp1/1nnqbN1p/3p4/2pP4/2P3Q1/PPB2PPP/R1B1R1K1 w - - 0 1",
-                depth: 13,
-            },
-        ];
-
         let mut total_nodes = 0;
         let mut total_time_ms = 0;
 
         println!("\n{:=^80}", " BENCHMARK SUITE ");
 
-        for pos_def in &positions {
+        for pos_def in &BENCH_POSITIONS {
             let pos = Position::from_fen(pos_def.fen)
                 .unwrap_or_else(|_| panic!("Invalid FEN: {}", pos_def.fen));
-            let mut tt = TranspositionTable::new_mb(256);
+            let tt = TranspositionTable::new_mb(256);
 
             println!("\nRunning: {}", pos_def.name);
             println!("FEN: {}", pos_def.fen);
 
             let start = Instant::now();
-            let result = search(&pos, pos_def.depth, None, &mut tt);
+            let result = search(&pos, pos_def.depth, None, &tt);
             let duration = start.elapsed();
 
             if let Some(info) = result {
                 let time_ms = duration.as_millis() as u64;
-                let nps = if time_ms > 0 {
-                    (info.nodes * 1000) / time_ms
-                } else {
-                    0
-                };
+                let nps = (info.nodes * 1000).checked_div(time_ms).unwrap_or(0);
 
                 total_nodes += info.nodes;
                 total_time_ms += time_ms;
 
                 println!("{:<80}", "");
                 println!("Best Move: {}", info.best_move.to_uci());
-                println!("Score:     {:<10} (cp)", info.score);
+                println!("Score:     {:<10}", info.score.to_string());
                 println!("Depth:     {:<10}", info.depth);
                 println!("Nodes:     {:<10}", info.nodes);
                 println!("Time:      {:.3}s", duration.as_secs_f64());
@@ -100,11 +95,7 @@ fn run_benchmark_suite() {
             }
         }
 
-        let total_nps = if total_time_ms > 0 {
-            (total_nodes * 1000) / total_time_ms
-        } else {
-            0
-        };
+        let total_nps = (total_nodes * 1000).checked_div(total_time_ms).unwrap_or(0);
 
         println!("\n{:=^80}", " SUMMARY ");
         println!("Total Nodes: {}", total_nodes);
@@ -113,3 +104,42 @@ fn run_benchmark_suite() {
         println!("{:=^80}\n", "");
     });
 }
+
+/// Table sizes for the node-count comparison, from roomy down to heavy replacement pressure.
+const TT_PRESSURE_SIZES_MB: [usize; 3] = [256, 16, 1];
+
+#[test]
+#[ignore = "Long running benchmark"]
+fn run_tt_node_count_suite() {
+    run_with_large_stack(|| {
+        init_lmr();
+
+        // pesto keeps the search deterministic and independent of the nnue networks, so node
+        // counts only move when the search or the table replacement policy changes.
+        println!("\n{:=^80}", " TT NODE COUNTS ");
+
+        for mb in TT_PRESSURE_SIZES_MB {
+            let mut total_nodes = 0;
+
+            for pos_def in &BENCH_POSITIONS {
+                let pos = Position::from_fen(pos_def.fen)
+                    .unwrap_or_else(|_| panic!("Invalid FEN: {}", pos_def.fen));
+                let tt = TranspositionTable::new_mb(mb);
+
+                let info = search_with_eval(
+                    &pos,
+                    pos_def.depth,
+                    SearchLimits::infinite(),
+                    &tt,
+                    PestoProvider::new(),
+                )
+                .expect("benchmark search should return a result");
+
+                println!("{:>4} MB  {:<28} {:>10}", mb, pos_def.name, info.nodes);
+                total_nodes += info.nodes;
+            }
+
+            println!("{:>4} MB  {:<28} {:>10}\n", mb, "Total", total_nodes);
+        }
+    });
+}
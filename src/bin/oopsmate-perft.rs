@@ -0,0 +1,84 @@
+use oops_mate::movegen::{parse_perft_epd, run_perft_epd, PerftTable};
+use std::process::ExitCode;
+use std::time::Instant;
+use strikes::warmup_attack_tables;
+
+const USAGE: &str = "usage: oopsmate-perft <suite.epd> [--depth N] [--threads N] [--hash MB]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(path) = args.first() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let mut max_depth = u8::MAX;
+    let mut threads = 1;
+    let mut hash_mb = 64;
+
+    let mut i = 1;
+    while i < args.len() {
+        let value = args.get(i + 1).and_then(|v| v.parse::<usize>().ok());
+        match (args[i].as_str(), value) {
+            ("--depth", Some(depth)) => max_depth = depth.min(u8::MAX as usize) as u8,
+            ("--threads", Some(count)) => threads = count.max(1),
+            ("--hash", Some(mb)) => hash_mb = mb,
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::FAILURE;
+            }
+        }
+        i += 2;
+    }
+
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let cases = match parse_perft_epd(&text) {
+        Ok(cases) => cases,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    warmup_attack_tables();
+    // a zero-sized hash disables the count cache entirely.
+    let table = (hash_mb > 0).then(|| PerftTable::new_mb(hash_mb));
+
+    let start = Instant::now();
+    let mismatches = match run_perft_epd(&cases, max_depth, threads, table.as_ref()) {
+        Ok(mismatches) => mismatches,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for mismatch in &mismatches {
+        println!(
+            "FAIL {} depth {}: expected {}, got {}",
+            mismatch.fen, mismatch.depth, mismatch.expected, mismatch.actual
+        );
+        for (mv, nodes) in &mismatch.divide {
+            println!("  {}: {}", mv, nodes);
+        }
+    }
+
+    println!(
+        "{} positions, {} mismatches, {} ms",
+        cases.len(),
+        mismatches.len(),
+        start.elapsed().as_millis()
+    );
+
+    if mismatches.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
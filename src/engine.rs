@@ -1,5 +1,5 @@
 use crate::eval::EvalProvider;
-use crate::search::{search_with_eval, SearchInfo, SearchLimits};
+use crate::search::{search_with_options, SearchInfo, SearchLimits, SearchOptions};
 use crate::tpt::TranspositionTable;
 use crate::Position;
 
@@ -8,6 +8,7 @@ pub struct Engine<E: EvalProvider> {
     pub position: Position,
     pub tt: TranspositionTable,
     pub eval: E,
+    pub options: SearchOptions,
 }
 
 impl<E: EvalProvider> Engine<E> {
@@ -17,16 +18,18 @@ impl<E: EvalProvider> Engine<E> {
             position: Position::new(),
             tt: TranspositionTable::new_mb(256),
             eval,
+            options: SearchOptions::new(),
         }
     }
 
     /// Searches the current position with the configured evaluation provider.
     pub fn search(&mut self, max_depth: u8, max_time_ms: Option<u64>) -> Option<SearchInfo> {
-        search_with_eval(
+        search_with_options(
             &self.position,
             max_depth,
             SearchLimits::from_max_time(max_time_ms),
-            &mut self.tt,
+            &self.options,
+            &self.tt,
             self.eval.clone(),
         )
     }
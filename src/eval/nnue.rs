@@ -194,13 +194,7 @@ fn build_move_delta(pos: &Position, mv: Move) -> MoveDelta {
                 .expect("en passant move should fit inside MoveDelta");
         }
         MoveType::Castle => {
-            let (rook_from, rook_to) = match to {
-                6 => (7, 5),
-                2 => (0, 3),
-                62 => (63, 61),
-                58 => (56, 59),
-                _ => panic!("invalid castle move"),
-            };
+            let (rook_from, rook_to) = pos.castling_rook_move(to);
             let rook_piece = map_piece(Piece::Rook, color);
             delta
                 .push_move(from, to, moving_piece, moving_piece)
@@ -10,6 +10,8 @@ pub mod engine;
 pub mod eval;
 /// Legal move generation and attack detection.
 pub mod movegen;
+/// PGN game import and export.
+pub mod pgn;
 /// Board state, FEN parsing, hashing, and make/unmake logic.
 pub mod position;
 /// Search orchestration, pruning, and root reporting.
@@ -31,10 +33,12 @@ pub mod zobrist;
 pub use engine::Engine;
 /// Evaluation providers and trait.
 pub use eval::{EvalProvider, NnueProvider, PestoProvider};
-/// The engine board representation.
-pub use position::Position;
+/// The engine board representation and its FEN parse errors.
+pub use position::{FenError, Position};
 /// Common engine types re-exported at the crate root.
 pub use types::*;
 
 #[cfg(test)]
 mod benchmark_tests;
+#[cfg(test)]
+mod perft_tests;
@@ -1,7 +1,10 @@
 //! Legal move generation.
 //!
 //! The implementation is split by piece family and by search use-case so that
-//! full generation and capture-only generation stay easy to follow.
+//! full, capture-only and quiet-only generation stay easy to follow. `perft`
+//! walks the generated tree to verify it against known node counts, either
+//! directly or from an EPD suite. Moves from outside the generator, such as
+//! transposition-table moves, are checked with `is_pseudo_legal` and `is_legal`.
 
 mod attacks;
 mod captures;
@@ -9,6 +12,14 @@ mod constraints;
 mod generation;
 mod king;
 mod leapers;
+mod legality;
 mod pawn_captures;
+mod pawn_quiets;
 mod pawns;
+mod perft;
+mod perft_epd;
+mod quiets;
 mod sliders;
+
+pub use perft::PerftTable;
+pub use perft_epd::{parse_perft_epd, run_perft_epd, PerftCase, PerftMismatch};
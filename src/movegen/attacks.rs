@@ -18,8 +18,19 @@ impl Position {
 
     #[inline(always)]
     pub fn is_square_attacked_with_blockers(&self, sq: usize, by: Color, blockers: u64) -> bool {
-        let attackers = self.colors[by as usize].0;
+        self.is_square_attacked_by(sq, by, self.colors[by as usize].0, blockers)
+    }
 
+    /// Returns `true` if any piece in `attackers`, all of color `by`, attacks `sq`
+    /// through `blockers`.
+    #[inline(always)]
+    pub(super) fn is_square_attacked_by(
+        &self,
+        sq: usize,
+        by: Color,
+        attackers: u64,
+        blockers: u64,
+    ) -> bool {
         if KNIGHT_ATTACKS[sq] & self.pieces[Piece::Knight as usize].0 & attackers != 0 {
             return true;
         }
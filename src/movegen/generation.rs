@@ -35,4 +35,22 @@ impl Position {
         );
         self.gen_king_captures(collector, enemies);
     }
+
+    /// Generates the legal moves [`Position::generate_captures`] leaves out:
+    /// quiet moves, castling and under-promotions.
+    ///
+    /// The two lists are disjoint and together match [`Position::generate_moves`],
+    /// so a staged move picker can generate them one at a time.
+    #[inline(always)]
+    pub fn generate_quiets(&self, collector: &mut MoveCollector) {
+        let (pinned, check_mask) = get_constraints(self);
+        let empty = !self.occupied().0;
+
+        self.gen_pawn_quiets(collector, pinned, check_mask);
+        self.gen_piece_quiets::<{ Piece::Knight as usize }>(collector, pinned, check_mask, empty);
+        self.gen_piece_quiets::<{ Piece::Bishop as usize }>(collector, pinned, check_mask, empty);
+        self.gen_piece_quiets::<{ Piece::Rook as usize }>(collector, pinned, check_mask, empty);
+        self.gen_piece_quiets::<{ Piece::Queen as usize }>(collector, pinned, check_mask, empty);
+        self.gen_king_quiets(collector, empty);
+    }
 }
@@ -51,42 +51,87 @@ impl Position {
         }
     }
 
+    /// Generates castling for both standard chess and Chess960.
+    ///
+    /// Every square the king and rook pass over or land on must be empty apart
+    /// from the two castling pieces, and the king may not pass through or land
+    /// on an attacked square. Attacks are tested with the castling rook lifted,
+    /// since in Chess960 it can be the piece shielding the king's target square.
+    pub(super) fn gen_king_quiets(&self, collector: &mut MoveCollector, empty: u64) {
+        let king_sq = self.our(Piece::King).0.trailing_zeros() as usize;
+        let enemy = self.side_to_move.flip();
+        let blockers_without_king = self.occupied().0 & !(1u64 << king_sq);
+
+        let mut attacks = KING_ATTACKS[king_sq] & empty;
+        while attacks != 0 {
+            let to = attacks.trailing_zeros() as usize;
+            attacks &= attacks - 1;
+
+            if !self.is_square_attacked_with_blockers(to, enemy, blockers_without_king) {
+                collector.push(Move::new(king_sq, to, MoveType::Quiet));
+            }
+        }
+
+        if !self.is_in_check() {
+            self.gen_castling(collector, king_sq, enemy);
+        }
+    }
+
     fn gen_castling(&self, collector: &mut MoveCollector, king_sq: usize, enemy: Color) {
-        let occupied = self.occupied().0;
+        let color = self.side_to_move;
 
-        match self.side_to_move {
-            Color::White => {
-                if self.castling_rights.can_castle_kingside(Color::White)
-                    && (occupied & 0x60) == 0
-                    && !self.is_square_attacked(5, enemy)
-                    && !self.is_square_attacked(6, enemy)
-                {
-                    collector.push(Move::new(king_sq, 6, MoveType::Castle));
-                }
-                if self.castling_rights.can_castle_queenside(Color::White)
-                    && (occupied & 0x0E) == 0
-                    && !self.is_square_attacked(3, enemy)
-                    && !self.is_square_attacked(2, enemy)
-                {
-                    collector.push(Move::new(king_sq, 2, MoveType::Castle));
-                }
+        for kingside in [true, false] {
+            if self.can_castle(king_sq, kingside, enemy) {
+                let king_to = Position::castling_king_target(color, kingside);
+                collector.push(Move::new(king_sq, king_to, MoveType::Castle));
             }
-            Color::Black => {
-                if self.castling_rights.can_castle_kingside(Color::Black)
-                    && (occupied & 0x6000000000000000) == 0
-                    && !self.is_square_attacked(61, enemy)
-                    && !self.is_square_attacked(62, enemy)
-                {
-                    collector.push(Move::new(king_sq, 62, MoveType::Castle));
-                }
-                if self.castling_rights.can_castle_queenside(Color::Black)
-                    && (occupied & 0x0E00000000000000) == 0
-                    && !self.is_square_attacked(59, enemy)
-                    && !self.is_square_attacked(58, enemy)
-                {
-                    collector.push(Move::new(king_sq, 58, MoveType::Castle));
-                }
+        }
+    }
+
+    /// Returns `true` if the side to move holds the castling right on one side,
+    /// the king and rook paths are clear, and no square the king crosses is attacked.
+    ///
+    /// Whether the king starts in check is left to the caller.
+    pub(super) fn can_castle(&self, king_sq: usize, kingside: bool, enemy: Color) -> bool {
+        let color = self.side_to_move;
+        let occupied = self.occupied().0;
+
+        let has_right = if kingside {
+            self.castling_rights.can_castle_kingside(color)
+        } else {
+            self.castling_rights.can_castle_queenside(color)
+        };
+        if !has_right {
+            return false;
+        }
+
+        let king_to = Position::castling_king_target(color, kingside);
+        let (rook_from, rook_to) = self.castling_rook_move(king_to);
+
+        let castlers = (1u64 << king_sq) | (1u64 << rook_from);
+        let path = span(king_sq, king_to) | span(rook_from, rook_to);
+        if path & occupied & !castlers != 0 {
+            return false;
+        }
+
+        let blockers = occupied & !castlers;
+        let mut king_path = span(king_sq, king_to) & !(1u64 << king_sq);
+        while king_path != 0 {
+            let sq = king_path.trailing_zeros() as usize;
+            king_path &= king_path - 1;
+
+            if self.is_square_attacked_with_blockers(sq, enemy, blockers) {
+                return false;
             }
         }
+
+        true
     }
 }
+
+/// Squares from `a` to `b` inclusive on one rank.
+#[inline(always)]
+const fn span(a: usize, b: usize) -> u64 {
+    let (lo, hi) = if a < b { (a, b) } else { (b, a) };
+    (u64::MAX >> (63 - hi)) & (u64::MAX << lo)
+}
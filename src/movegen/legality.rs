@@ -0,0 +1,288 @@
+//! Validation of moves that did not come from the generator.
+//!
+//! Transposition-table moves, killers and counter moves are plain 16-bit
+//! encodings remembered from other positions. A staged picker wants to try them
+//! before generating anything, and a hash collision can hand back a move that
+//! makes no sense here, so each one is checked against the board first.
+//!
+//! The check is split the same way the generator thinks about moves:
+//! [`Position::is_pseudo_legal`] asks whether the encoding describes a move the
+//! generator could emit if pins and checks were ignored, and
+//! [`Position::is_legal`] additionally requires that our king is not left in check.
+
+use crate::{
+    position::Position,
+    types::{Color, Move, MoveType, Piece},
+};
+use std::arch::x86_64::_pext_u64;
+
+use strikes::{
+    BISHOP_ATTACKS, BISHOP_MASKS, KING_ATTACKS, KNIGHT_ATTACKS, PAWN_ATTACKS, ROOK_ATTACKS,
+    ROOK_MASKS,
+};
+
+/// Raw move-type nibbles that no [`MoveType`] variant uses.
+const UNUSED_MOVE_TYPES: std::ops::RangeInclusive<u16> = 5..=7;
+
+impl Position {
+    /// Returns `true` if `mv` is a well-formed move for the side to move that the
+    /// generator would produce were pins and checks ignored.
+    ///
+    /// Any 16-bit value may be passed, including `Move(0)`. The move-type flags
+    /// must agree with the board: captures land on an enemy piece other than the
+    /// king, quiet moves on an empty square, and promotions exactly on the last
+    /// rank. Castling is validated in full, attacked squares included, since
+    /// the generator never emits an unsafe castle either.
+    pub fn is_pseudo_legal(&self, mv: Move) -> bool {
+        if UNUSED_MOVE_TYPES.contains(&(mv.0 >> 12)) {
+            return false;
+        }
+
+        let from = mv.from();
+        let to = mv.to();
+        let color = self.side_to_move;
+        let piece = match self.piece_at(from) {
+            Some((piece, owner)) if owner == color => piece,
+            _ => return false,
+        };
+        let move_type = mv.move_type();
+
+        if move_type == MoveType::Castle {
+            return piece == Piece::King && self.is_castle_available(from, to);
+        }
+
+        // chess960 castling may leave the king in place, but nothing else can.
+        if from == to {
+            return false;
+        }
+
+        let target = 1u64 << to;
+        if self.us().0 & target != 0 || self.their(Piece::King).0 & target != 0 {
+            return false;
+        }
+
+        if move_type == MoveType::EnPassant {
+            return piece == Piece::Pawn
+                && self.en_passant == Some(to as u8)
+                && PAWN_ATTACKS[color as usize][from] & target != 0;
+        }
+
+        if mv.is_capture() != (self.them().0 & target != 0) {
+            return false;
+        }
+
+        if piece == Piece::Pawn {
+            return self.is_pawn_move_consistent(mv, color);
+        }
+
+        matches!(move_type, MoveType::Quiet | MoveType::Capture)
+            && piece_attacks(piece, from, self.occupied().0) & target != 0
+    }
+
+    /// Returns `true` if `mv` is legal in this position.
+    ///
+    /// Agrees exactly with [`Position::generate_moves`], so a move that passes can
+    /// be handed straight to [`Position::make_move`].
+    pub fn is_legal(&self, mv: Move) -> bool {
+        self.is_pseudo_legal(mv) && self.keeps_king_safe(mv)
+    }
+
+    /// Checks the pawn-specific rules for a move already known to start on one
+    /// of our pawns and to agree with the occupancy of its target square.
+    fn is_pawn_move_consistent(&self, mv: Move, color: Color) -> bool {
+        let from = mv.from();
+        let to = mv.to();
+        let (forward, start_rank, last_rank) = match color {
+            Color::White => (8i32, 1, 7),
+            Color::Black => (-8i32, 6, 0),
+        };
+
+        if mv.is_promotion() != (to / 8 == last_rank) {
+            return false;
+        }
+
+        let single = from as i32 + forward;
+        match mv.move_type() {
+            MoveType::DoublePush => {
+                from / 8 == start_rank
+                    && to as i32 == single + forward
+                    && self.piece_at(single as usize).is_none()
+            }
+            _ if mv.is_capture() => PAWN_ATTACKS[color as usize][from] & (1u64 << to) != 0,
+            _ => to as i32 == single,
+        }
+    }
+
+    /// Returns `true` if castling with the king on `from` to `to` is allowed.
+    fn is_castle_available(&self, from: usize, to: usize) -> bool {
+        let color = self.side_to_move;
+        let enemy = color.flip();
+        let kingside = if to == Position::castling_king_target(color, true) {
+            true
+        } else if to == Position::castling_king_target(color, false) {
+            false
+        } else {
+            return false;
+        };
+
+        !self.is_square_attacked(from, enemy) && self.can_castle(from, kingside, enemy)
+    }
+
+    /// Returns `true` if playing the pseudo-legal `mv` leaves our king unattacked.
+    fn keeps_king_safe(&self, mv: Move) -> bool {
+        // castling already checked every square the king crosses.
+        if mv.move_type() == MoveType::Castle {
+            return true;
+        }
+
+        let from = mv.from();
+        let to = mv.to();
+        let enemy = self.side_to_move.flip();
+        let king_sq = self.our(Piece::King).0.trailing_zeros() as usize;
+
+        if from == king_sq {
+            let blockers = self.occupied().0 & !(1u64 << from);
+            return !self.is_square_attacked_with_blockers(to, enemy, blockers);
+        }
+
+        let captured_sq = if mv.move_type() == MoveType::EnPassant {
+            match self.side_to_move {
+                Color::White => to - 8,
+                Color::Black => to + 8,
+            }
+        } else {
+            to
+        };
+
+        let captured = 1u64 << captured_sq;
+        let blockers = (self.occupied().0 & !(1u64 << from) & !captured) | (1u64 << to);
+        let attackers = self.them().0 & !captured;
+
+        !self.is_square_attacked_by(king_sq, enemy, attackers, blockers)
+    }
+}
+
+/// Squares attacked by a non-pawn `piece` on `sq` through `blockers`.
+#[inline(always)]
+fn piece_attacks(piece: Piece, sq: usize, blockers: u64) -> u64 {
+    let bishop = || unsafe { BISHOP_ATTACKS[sq][_pext_u64(blockers, BISHOP_MASKS[sq]) as usize] };
+    let rook = || unsafe { ROOK_ATTACKS[sq][_pext_u64(blockers, ROOK_MASKS[sq]) as usize] };
+
+    match piece {
+        Piece::Knight => KNIGHT_ATTACKS[sq],
+        Piece::Bishop => bishop(),
+        Piece::Rook => rook(),
+        Piece::Queen => bishop() | rook(),
+        Piece::King => KING_ATTACKS[sq],
+        Piece::Pawn => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Move, MoveCollector, MoveType, Position};
+
+    fn legal_moves(pos: &Position) -> Vec<Move> {
+        let mut collector = MoveCollector::new();
+        pos.generate_moves(&mut collector);
+        collector.as_slice().to_vec()
+    }
+
+    fn position(fen: &str) -> Position {
+        Position::from_fen(fen).expect("valid FEN")
+    }
+
+    #[test]
+    fn every_encoding_agrees_with_the_generator() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "8/8/8/K2pP2r/8/8/8/7k w - d6 0 1",
+        ] {
+            let pos = position(fen);
+            let legal = legal_moves(&pos);
+
+            for raw in 0..=u16::MAX {
+                let mv = Move(raw);
+                let expected = legal.contains(&mv);
+                assert_eq!(pos.is_legal(mv), expected, "{mv:?} in {fen}");
+                if expected {
+                    assert!(pos.is_pseudo_legal(mv), "{mv:?} in {fen}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn moves_from_another_position_are_rejected() {
+        // a tt collision hands back a move that was legal somewhere else.
+        let start = position("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let kiwipete =
+            position("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+        let start_moves = legal_moves(&start);
+
+        for mv in legal_moves(&kiwipete) {
+            assert_eq!(start.is_legal(mv), start_moves.contains(&mv), "{mv:?}");
+        }
+        assert!(!start.is_legal(Move(0)));
+        // g1f3 with a capture flag onto an empty square.
+        assert!(!start.is_pseudo_legal(Move::new(6, 21, MoveType::Capture)));
+    }
+
+    #[test]
+    fn en_passant_needs_the_square_and_a_safe_king() {
+        let open = position("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1");
+        let exd6 = Move::new(36, 43, MoveType::EnPassant);
+        assert!(open.is_legal(exd6));
+
+        let stale = position("4k3/8/8/3pP3/8/8/8/4K3 w - - 0 1");
+        assert!(!stale.is_pseudo_legal(exd6));
+
+        // both pawns leave the fifth rank, exposing the king to the rook.
+        let pinned = position("8/8/8/K2pP2r/8/8/8/7k w - d6 0 1");
+        assert!(pinned.is_pseudo_legal(exd6));
+        assert!(!pinned.is_legal(exd6));
+    }
+
+    #[test]
+    fn castling_through_or_out_of_check_is_rejected() {
+        let kingside = Move::new(4, 6, MoveType::Castle);
+        let queenside = Move::new(4, 2, MoveType::Castle);
+
+        let through_f1 = position("4k3/8/8/8/8/8/5r2/R3K2R w KQ - 0 1");
+        assert!(!through_f1.is_pseudo_legal(kingside));
+        assert!(through_f1.is_legal(queenside));
+
+        let in_check = position("4k3/8/8/8/8/8/4r3/R3K2R w KQ - 0 1");
+        assert!(!in_check.is_pseudo_legal(kingside));
+        assert!(!in_check.is_pseudo_legal(queenside));
+
+        let no_right = position("4k3/8/8/8/8/8/8/R3K2R w Q - 0 1");
+        assert!(!no_right.is_pseudo_legal(kingside));
+    }
+
+    #[test]
+    fn pinned_pieces_stay_on_the_pin_line() {
+        let knight = position("4k3/4r3/8/8/8/8/4N3/4K3 w - - 0 1");
+        let ne2c3 = Move::new(12, 18, MoveType::Quiet);
+        assert!(knight.is_pseudo_legal(ne2c3));
+        assert!(!knight.is_legal(ne2c3));
+
+        let rook = position("4k3/4r3/8/8/8/8/4R3/4K3 w - - 0 1");
+        assert!(rook.is_legal(Move::new(12, 36, MoveType::Quiet)));
+        assert!(rook.is_legal(Move::new(12, 52, MoveType::Capture)));
+        assert!(rook.is_pseudo_legal(Move::new(12, 11, MoveType::Quiet)));
+        assert!(!rook.is_legal(Move::new(12, 11, MoveType::Quiet)));
+    }
+
+    #[test]
+    fn the_enemy_king_is_never_a_capture_target() {
+        // passing leaves black to move with the white king en prise.
+        let mut pos = position("4k3/8/8/8/8/8/4r3/4K3 w - - 0 1");
+        pos.make_null_move();
+
+        assert!(!pos.is_pseudo_legal(Move::new(12, 4, MoveType::Capture)));
+        assert!(!pos.is_pseudo_legal(Move::new(12, 4, MoveType::Quiet)));
+    }
+}
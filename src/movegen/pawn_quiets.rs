@@ -0,0 +1,90 @@
+use crate::{
+    position::Position,
+    types::{Color, Move, MoveCollector, MoveType, Piece},
+};
+use strikes::{PAWN_ATTACKS, THROUGH};
+
+const UNDER_PROMOTIONS: [MoveType; 3] = [
+    MoveType::PromotionRook,
+    MoveType::PromotionBishop,
+    MoveType::PromotionKnight,
+];
+
+const UNDER_CAPTURE_PROMOTIONS: [MoveType; 3] = [
+    MoveType::CapturePromotionRook,
+    MoveType::CapturePromotionBishop,
+    MoveType::CapturePromotionKnight,
+];
+
+impl Position {
+    /// Generates the pawn moves `gen_pawn_captures` leaves out: pushes, double
+    /// pushes and every under-promotion, capturing or not.
+    #[inline(always)]
+    pub(super) fn gen_pawn_quiets(
+        &self,
+        collector: &mut MoveCollector,
+        pinned: u64,
+        check_mask: u64,
+    ) {
+        let color = self.side_to_move;
+        let pawns = self.our(Piece::Pawn).0;
+        let empty = !self.occupied().0;
+        let enemies = self.them().0 & !self.their(Piece::King).0;
+        let king_sq = self.our(Piece::King).0.trailing_zeros() as usize;
+        let (start_rank, last_rank) = match color {
+            Color::White => (1, 7),
+            Color::Black => (6, 0),
+        };
+        let forward = |sq: usize| match color {
+            Color::White => sq + 8,
+            Color::Black => sq - 8,
+        };
+
+        let mut bb = pawns;
+        while bb != 0 {
+            let from = bb.trailing_zeros() as usize;
+            bb &= bb - 1;
+
+            let pin_ray = if (pinned >> from) & 1 != 0 {
+                THROUGH[king_sq][from]
+            } else {
+                !0u64
+            };
+            let allowed = pin_ray & check_mask;
+
+            let to = forward(from);
+            if (empty >> to) & 1 != 0 {
+                if (allowed >> to) & 1 != 0 {
+                    if to / 8 == last_rank {
+                        for move_type in UNDER_PROMOTIONS {
+                            collector.push(Move::new(from, to, move_type));
+                        }
+                    } else {
+                        collector.push(Move::new(from, to, MoveType::Quiet));
+                    }
+                }
+
+                if from / 8 == start_rank {
+                    let to2 = forward(to);
+                    if (empty >> to2) & 1 != 0 && (allowed >> to2) & 1 != 0 {
+                        collector.push(Move::new(from, to2, MoveType::DoublePush));
+                    }
+                }
+            }
+
+            if to / 8 != last_rank {
+                continue;
+            }
+
+            let mut attacks = PAWN_ATTACKS[color as usize][from] & enemies & allowed;
+            while attacks != 0 {
+                let to = attacks.trailing_zeros() as usize;
+                attacks &= attacks - 1;
+
+                for move_type in UNDER_CAPTURE_PROMOTIONS {
+                    collector.push(Move::new(from, to, move_type));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Perft node counting.
+//!
+//! `perft` makes every move down to depth zero and is the reference walk.
+//! `perft_bulk` is the fast variant used for regression runs: it counts the
+//! legal moves at depth one instead of making them, can memoize subtree counts
+//! in a [`PerftTable`], and can split the root moves across threads.
+//!
+//! The table uses the same lock-free scheme as the transposition table: each
+//! slot holds a packed payload and `hash ^ payload`, so a torn read from a
+//! concurrent store fails validation and is treated as a miss.
+
+use crate::{
+    position::Position,
+    types::{Move, MoveCollector},
+};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::thread;
+
+const DEPTH_SHIFT: u32 = 56;
+const NODES_MASK: u64 = (1 << DEPTH_SHIFT) - 1;
+
+#[derive(Default)]
+struct PerftSlot {
+    data: AtomicU64,
+    signature: AtomicU64,
+}
+
+/// Shared hash table of perft subtree counts keyed by position hash and depth.
+pub struct PerftTable {
+    slots: Box<[PerftSlot]>,
+    mask: usize,
+}
+
+impl PerftTable {
+    /// Allocates a table of roughly `mb` megabytes, rounded down to a power of two.
+    pub fn new_mb(mb: usize) -> Self {
+        let bytes = mb.max(1) * 1024 * 1024;
+        let slots = (bytes / std::mem::size_of::<PerftSlot>()).max(1);
+        let size = if slots.is_power_of_two() {
+            slots
+        } else {
+            slots.next_power_of_two() >> 1
+        };
+
+        PerftTable {
+            slots: (0..size).map(|_| PerftSlot::default()).collect(),
+            mask: size - 1,
+        }
+    }
+
+    #[inline(always)]
+    fn slot(&self, hash: u64) -> &PerftSlot {
+        &self.slots[hash as usize & self.mask]
+    }
+
+    /// Returns the stored count for `hash` at exactly `depth`, if present.
+    #[inline(always)]
+    pub fn probe(&self, hash: u64, depth: u8) -> Option<u64> {
+        let slot = self.slot(hash);
+        let data = slot.data.load(Ordering::Relaxed);
+        let signature = slot.signature.load(Ordering::Relaxed);
+
+        if data != 0 && signature ^ data == hash && (data >> DEPTH_SHIFT) as u8 == depth {
+            Some(data & NODES_MASK)
+        } else {
+            None
+        }
+    }
+
+    /// Stores the count for `hash` at `depth`, always replacing the slot.
+    #[inline(always)]
+    pub fn store(&self, hash: u64, depth: u8, nodes: u64) {
+        debug_assert!(nodes <= NODES_MASK);
+        let data = ((depth as u64) << DEPTH_SHIFT) | (nodes & NODES_MASK);
+        let slot = self.slot(hash);
+        slot.data.store(data, Ordering::Relaxed);
+        slot.signature.store(hash ^ data, Ordering::Relaxed);
+    }
+}
+
+impl Position {
+    /// Counts the leaf nodes of the legal move tree to `depth` plies.
+    ///
+    /// Every move is made and unmade down to depth zero, so this exercises
+    /// make/unmake and incremental hashing as well as move generation.
+    pub fn perft(&mut self, depth: u8) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let mut collector = MoveCollector::new();
+        self.generate_moves(&mut collector);
+
+        let mut nodes = 0;
+        for &mv in collector.as_slice() {
+            self.make_move(mv);
+            nodes += self.perft(depth - 1);
+            self.unmake_move(mv);
+        }
+
+        nodes
+    }
+
+    /// Returns the perft count below each legal root move, in generation order.
+    pub fn perft_divide(&mut self, depth: u8) -> Vec<(Move, u64)> {
+        if depth == 0 {
+            return Vec::new();
+        }
+
+        let mut collector = MoveCollector::new();
+        self.generate_moves(&mut collector);
+
+        collector
+            .as_slice()
+            .iter()
+            .map(|&mv| {
+                self.make_move(mv);
+                let nodes = self.perft(depth - 1);
+                self.unmake_move(mv);
+                (mv, nodes)
+            })
+            .collect()
+    }
+
+    /// Counts perft leaves with bulk counting at depth one and an optional count cache.
+    pub fn perft_bulk(&mut self, depth: u8, table: Option<&PerftTable>) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let mut collector = MoveCollector::new();
+        self.generate_moves(&mut collector);
+
+        // bulk counting: legal generation means the move count is the leaf count.
+        if depth == 1 {
+            return collector.len() as u64;
+        }
+
+        if let Some(nodes) = table.and_then(|table| table.probe(self.hash, depth)) {
+            return nodes;
+        }
+
+        let mut nodes = 0;
+        for &mv in collector.as_slice() {
+            self.make_move(mv);
+            nodes += self.perft_bulk(depth - 1, table);
+            self.unmake_move(mv);
+        }
+
+        if let Some(table) = table {
+            table.store(self.hash, depth, nodes);
+        }
+
+        nodes
+    }
+
+    /// Bulk-counting divide that splits the root moves across `threads` workers.
+    ///
+    /// Results stay in generation order regardless of which thread counted them.
+    pub fn perft_divide_bulk(
+        &self,
+        depth: u8,
+        threads: usize,
+        table: Option<&PerftTable>,
+    ) -> Vec<(Move, u64)> {
+        if depth == 0 {
+            return Vec::new();
+        }
+
+        let mut collector = MoveCollector::new();
+        self.generate_moves(&mut collector);
+        let moves = collector.as_slice();
+        let counts: Vec<AtomicU64> = moves.iter().map(|_| AtomicU64::new(0)).collect();
+        let next = AtomicUsize::new(0);
+
+        // root split: workers pull the next unclaimed root move until none remain.
+        thread::scope(|scope| {
+            for _ in 0..threads.clamp(1, moves.len().max(1)) {
+                scope.spawn(|| {
+                    let mut pos = self.clone();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(&mv) = moves.get(index) else {
+                            break;
+                        };
+
+                        pos.make_move(mv);
+                        let nodes = pos.perft_bulk(depth - 1, table);
+                        pos.unmake_move(mv);
+                        counts[index].store(nodes, Ordering::Relaxed);
+                    }
+                });
+            }
+        });
+
+        moves
+            .iter()
+            .zip(counts)
+            .map(|(&mv, nodes)| (mv, nodes.into_inner()))
+            .collect()
+    }
+}
@@ -0,0 +1,112 @@
+//! EPD perft suites.
+//!
+//! Each line holds a FEN followed by expected counts, for example
+//! `rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 ;D1 20 ;D2 400`.
+//! Blank lines and lines starting with `#` are skipped.
+
+use super::perft::PerftTable;
+use crate::{Move, Position};
+
+/// One EPD line: a position and its expected perft counts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PerftCase {
+    /// Position under test.
+    pub fen: String,
+    /// Expected `(depth, nodes)` pairs in file order.
+    pub expected: Vec<(u8, u64)>,
+}
+
+/// A depth whose count disagreed with the suite, with the divide at that depth.
+#[derive(Clone, Debug)]
+pub struct PerftMismatch {
+    /// Position that failed.
+    pub fen: String,
+    /// Depth that failed.
+    pub depth: u8,
+    /// Count recorded in the suite.
+    pub expected: u64,
+    /// Count produced by the move generator.
+    pub actual: u64,
+    /// Per-root-move counts at `depth`, in UCI notation.
+    pub divide: Vec<(String, u64)>,
+}
+
+/// Parses an EPD perft suite, reporting the 1-based line number of malformed entries.
+pub fn parse_perft_epd(text: &str) -> Result<Vec<PerftCase>, String> {
+    let mut cases = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split(';');
+        let fen = fields.next().unwrap_or_default().trim().to_string();
+        let mut expected = Vec::new();
+
+        for field in fields {
+            let mut tokens = field.split_whitespace();
+            let (Some(label), Some(nodes), None) = (tokens.next(), tokens.next(), tokens.next())
+            else {
+                return Err(format!("line {}: malformed field '{}'", index + 1, field));
+            };
+
+            let depth = label
+                .strip_prefix('D')
+                .and_then(|depth| depth.parse::<u8>().ok())
+                .ok_or_else(|| format!("line {}: bad depth label '{}'", index + 1, label))?;
+            let nodes = nodes
+                .parse::<u64>()
+                .map_err(|_| format!("line {}: bad node count '{}'", index + 1, nodes))?;
+            expected.push((depth, nodes));
+        }
+
+        cases.push(PerftCase { fen, expected });
+    }
+
+    Ok(cases)
+}
+
+/// Runs every case up to `max_depth`, returning the first mismatching depth of each position.
+pub fn run_perft_epd(
+    cases: &[PerftCase],
+    max_depth: u8,
+    threads: usize,
+    table: Option<&PerftTable>,
+) -> Result<Vec<PerftMismatch>, String> {
+    let mut mismatches = Vec::new();
+
+    for case in cases {
+        let pos = Position::from_fen(&case.fen).map_err(|e| format!("{}: {}", case.fen, e))?;
+
+        for &(depth, expected) in case.expected.iter().filter(|(d, _)| *d <= max_depth) {
+            let divide = pos.perft_divide_bulk(depth, threads, table);
+            let actual = if depth == 0 {
+                1
+            } else {
+                divide.iter().map(|&(_, nodes)| nodes).sum()
+            };
+
+            if actual != expected {
+                mismatches.push(PerftMismatch {
+                    fen: case.fen.clone(),
+                    depth,
+                    expected,
+                    actual,
+                    divide: divide_to_uci(&pos, &divide),
+                });
+                break;
+            }
+        }
+    }
+
+    Ok(mismatches)
+}
+
+fn divide_to_uci(pos: &Position, divide: &[(Move, u64)]) -> Vec<(String, u64)> {
+    divide
+        .iter()
+        .map(|&(mv, nodes)| (pos.move_to_uci(mv), nodes))
+        .collect()
+}
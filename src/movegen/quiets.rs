@@ -0,0 +1,124 @@
+use crate::{
+    position::Position,
+    types::{Move, MoveCollector, MoveType, Piece},
+};
+use std::arch::x86_64::_pext_u64;
+
+use strikes::{BISHOP_ATTACKS, BISHOP_MASKS, KNIGHT_ATTACKS, ROOK_ATTACKS, ROOK_MASKS, THROUGH};
+
+impl Position {
+    #[inline(always)]
+    pub(super) fn gen_piece_quiets<const PIECE: usize>(
+        &self,
+        collector: &mut MoveCollector,
+        pinned: u64,
+        check_mask: u64,
+        empty: u64,
+    ) {
+        let pieces = self
+            .our(unsafe { std::mem::transmute::<u8, Piece>(PIECE as u8) })
+            .0;
+
+        if PIECE == Piece::Knight as usize {
+            let pieces = pieces & !pinned;
+            let mut bb = pieces;
+            while bb != 0 {
+                let from = bb.trailing_zeros() as usize;
+                bb &= bb - 1;
+
+                let mut attacks = KNIGHT_ATTACKS[from] & empty & check_mask;
+                while attacks != 0 {
+                    let to = attacks.trailing_zeros() as usize;
+                    attacks &= attacks - 1;
+                    collector.push(Move::new(from, to, MoveType::Quiet));
+                }
+            }
+            return;
+        }
+
+        let blockers = !empty;
+        let king_sq = self.our(Piece::King).0.trailing_zeros() as usize;
+
+        let mut bb = pieces;
+        while bb != 0 {
+            let from = bb.trailing_zeros() as usize;
+            bb &= bb - 1;
+
+            let mut attacks = match PIECE {
+                2 => {
+                    let idx = unsafe { _pext_u64(blockers, BISHOP_MASKS[from]) as usize };
+                    BISHOP_ATTACKS[from][idx]
+                }
+                3 => {
+                    let idx = unsafe { _pext_u64(blockers, ROOK_MASKS[from]) as usize };
+                    ROOK_ATTACKS[from][idx]
+                }
+                4 => {
+                    let bishop_idx = unsafe { _pext_u64(blockers, BISHOP_MASKS[from]) as usize };
+                    let rook_idx = unsafe { _pext_u64(blockers, ROOK_MASKS[from]) as usize };
+                    BISHOP_ATTACKS[from][bishop_idx] | ROOK_ATTACKS[from][rook_idx]
+                }
+                _ => unreachable!(),
+            };
+
+            attacks &= empty;
+
+            if (pinned >> from) & 1 != 0 {
+                attacks &= THROUGH[king_sq][from];
+            }
+            attacks &= check_mask;
+
+            while attacks != 0 {
+                let to = attacks.trailing_zeros() as usize;
+                attacks &= attacks - 1;
+                collector.push(Move::new(from, to, MoveType::Quiet));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Move, MoveCollector, Position};
+
+    fn generated(pos: &Position, generate: impl Fn(&Position, &mut MoveCollector)) -> Vec<Move> {
+        let mut collector = MoveCollector::new();
+        generate(pos, &mut collector);
+        let mut moves = collector.as_slice().to_vec();
+        moves.sort_by_key(|mv| mv.0);
+        moves
+    }
+
+    #[test]
+    fn captures_and_quiets_partition_legal_moves() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            // castling both ways, pins and captures.
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            // promotions with and without capture, including under-promotions.
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            // en passant next to a horizontal pin.
+            "8/8/8/K2pP2r/8/8/8/7k w - d6 0 1",
+            // single check that can be blocked or captured, then a double check.
+            "4k3/8/8/8/1b6/P7/8/RN2K2R w KQ - 0 1",
+            "4k3/8/8/8/1b6/8/8/r3K2R w K - 0 1",
+            "4k3/8/8/8/7b/8/4r3/4K3 w - - 0 1",
+            // chess960 castling where the king stays on its square.
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+        ] {
+            let pos = Position::from_fen(fen).expect("valid FEN");
+            let legal = generated(&pos, Position::generate_moves);
+            let captures = generated(&pos, Position::generate_captures);
+            let quiets = generated(&pos, Position::generate_quiets);
+
+            assert!(
+                captures.iter().all(|mv| !quiets.contains(mv)),
+                "overlap in {fen}"
+            );
+
+            let mut staged = [captures, quiets].concat();
+            staged.sort_by_key(|mv| mv.0);
+            assert_eq!(staged, legal, "{fen}");
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use crate::movegen::{parse_perft_epd, run_perft_epd, PerftTable};
+use crate::Position;
+
+struct PerftPos {
+    name: &'static str,
+    fen: &'static str,
+    depth: u8,
+    nodes: u64,
+}
+
+/// The standard perft suite from the Chess Programming Wiki, at depths that
+/// keep an unoptimized test run short.
+const PERFT_SUITE: [PerftPos; 6] = [
+    PerftPos {
+        name: "Start Position",
+        fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        depth: 4,
+        nodes: 197_281,
+    },
+    PerftPos {
+        name: "KiwiPete",
+        fen: "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        depth: 3,
+        nodes: 97_862,
+    },
+    PerftPos {
+        name: "Position 3",
+        fen: "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        depth: 5,
+        nodes: 674_624,
+    },
+    PerftPos {
+        name: "Position 4",
+        fen: "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        depth: 4,
+        nodes: 422_333,
+    },
+    PerftPos {
+        name: "Position 5",
+        fen: "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        depth: 3,
+        nodes: 62_379,
+    },
+    PerftPos {
+        name: "Position 6",
+        fen: "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+        depth: 3,
+        nodes: 89_890,
+    },
+];
+
+#[test]
+fn standard_perft_suite_matches_known_counts() {
+    for entry in &PERFT_SUITE {
+        let mut pos = Position::from_fen(entry.fen).expect("valid FEN");
+        assert_eq!(
+            pos.perft(entry.depth),
+            entry.nodes,
+            "{} perft({})",
+            entry.name,
+            entry.depth
+        );
+    }
+}
+
+#[test]
+fn perft_leaves_the_position_unchanged() {
+    for entry in &PERFT_SUITE {
+        let mut pos = Position::from_fen(entry.fen).expect("valid FEN");
+        let hash = pos.hash();
+        pos.perft(2);
+        assert_eq!(pos.hash(), hash, "{}", entry.name);
+        assert!(pos.history.is_empty(), "{}", entry.name);
+    }
+}
+
+#[test]
+fn divide_sums_to_perft() {
+    let mut pos = Position::from_fen(PERFT_SUITE[1].fen).expect("valid FEN");
+    let divide = pos.perft_divide(3);
+
+    assert_eq!(divide.len(), 48);
+    assert_eq!(divide.iter().map(|&(_, nodes)| nodes).sum::<u64>(), 97_862);
+}
+
+#[test]
+fn chess960_perft_matches_known_counts() {
+    let mut pos =
+        Position::from_fen("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9")
+            .expect("valid FEN");
+    assert_eq!(pos.perft(3), 12_189);
+}
+
+#[test]
+#[ignore = "Long running perft"]
+fn standard_perft_suite_at_full_depth() {
+    let deep = [
+        (PERFT_SUITE[0].fen, 6, 119_060_324),
+        (PERFT_SUITE[1].fen, 5, 193_690_690),
+        (PERFT_SUITE[2].fen, 7, 178_633_661),
+        (PERFT_SUITE[3].fen, 5, 15_833_292),
+        (PERFT_SUITE[4].fen, 5, 89_941_194),
+        (PERFT_SUITE[5].fen, 5, 164_075_551),
+    ];
+
+    for (fen, depth, nodes) in deep {
+        let mut pos = Position::from_fen(fen).expect("valid FEN");
+        assert_eq!(pos.perft(depth), nodes, "{fen} perft({depth})");
+    }
+}
+
+#[test]
+fn bulk_perft_with_table_and_threads_matches_epd_suite() {
+    let cases = parse_perft_epd(include_str!("../archive/data/perft/standard.epd"))
+        .expect("valid EPD suite");
+    assert_eq!(cases.len(), 12);
+
+    let table = PerftTable::new_mb(4);
+    let mismatches = run_perft_epd(&cases, 3, 3, Some(&table)).expect("valid positions");
+    assert!(mismatches.is_empty(), "{:?}", mismatches);
+
+    let mut pos = Position::from_fen(PERFT_SUITE[3].fen).expect("valid FEN");
+    assert_eq!(pos.perft_bulk(4, Some(&table)), 422_333);
+    assert_eq!(pos.perft_bulk(4, None), 422_333);
+}
+
+#[test]
+fn epd_mismatch_reports_divide_of_failing_depth() {
+    let cases = parse_perft_epd(
+        "# deliberately wrong at depth 2\n\
+         rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 ;D1 20 ;D2 401 ;D3 8902\n",
+    )
+    .expect("valid EPD");
+
+    let mismatches = run_perft_epd(&cases, 3, 1, None).expect("valid positions");
+    assert_eq!(mismatches.len(), 1);
+
+    let mismatch = &mismatches[0];
+    assert_eq!(
+        (mismatch.depth, mismatch.expected, mismatch.actual),
+        (2, 401, 400)
+    );
+    assert_eq!(mismatch.divide.len(), 20);
+    assert!(mismatch.divide.contains(&("e2e4".to_string(), 20)));
+}
+
+#[test]
+fn malformed_epd_fields_are_rejected() {
+    assert!(parse_perft_epd("8/8/8/8/8/8/8/K6k w - - 0 1 ;D1").is_err());
+    assert!(parse_perft_epd("8/8/8/8/8/8/8/K6k w - - 0 1 ;X1 3").is_err());
+}
@@ -0,0 +1,209 @@
+//! PGN import and export.
+//!
+//! [`PgnReader`] walks a PGN file game by game, resolving SAN movetext against
+//! the position so every [`PgnMove`] carries a legal [`Move`]. Comments, NAGs
+//! (including `!`/`?` suffixes) and nested variations are kept; a comment in the
+//! engine-eval form written by match runners (`{+0.27/4 0.084s}`) is decoded
+//! into a [`PgnEval`], with any trailing text kept as the comment.
+//!
+//! [`PgnGame::to_pgn`] writes a game back out in export format, regenerating
+//! SAN from the moves and wrapping movetext below 80 columns.
+
+mod reader;
+#[cfg(test)]
+mod tests;
+mod writer;
+
+pub use reader::PgnReader;
+
+use crate::search::{Score, SearchInfo};
+use crate::{FenError, Move, Position};
+use std::fmt;
+
+/// Tag names of the PGN seven tag roster, in export order.
+const SEVEN_TAG_ROSTER: [&str; 7] = ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+
+/// One parsed or recorded game.
+#[derive(Clone, Debug)]
+pub struct PgnGame {
+    /// Tag pairs in file order.
+    pub tags: Vec<(String, String)>,
+    /// Position before the first move, from the `FEN` tag when present.
+    pub start: Position,
+    /// Comment preceding the first move.
+    pub comment: Option<String>,
+    /// Main line.
+    pub moves: Vec<PgnMove>,
+    /// Game termination marker: `1-0`, `0-1`, `1/2-1/2` or `*`.
+    pub result: String,
+}
+
+/// A move with its annotations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PgnMove {
+    /// The legal move played.
+    pub mv: Move,
+    /// Numeric annotation glyphs, e.g. `1` for `!`.
+    pub nags: Vec<u8>,
+    /// Engine evaluation decoded from the move comment.
+    pub eval: Option<PgnEval>,
+    /// Free-text comment following the move.
+    pub comment: Option<String>,
+    /// Alternatives to this move, each played from the position before it.
+    pub variations: Vec<Vec<PgnMove>>,
+}
+
+/// Engine evaluation attached to a move, from the mover's point of view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PgnEval {
+    /// Score after the move, as reported by the engine that played it.
+    pub score: Score,
+    /// Search depth reached.
+    pub depth: u8,
+    /// Time spent on the move, when recorded.
+    pub time_ms: Option<u64>,
+}
+
+/// Reasons a game cannot be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PgnError {
+    /// A tag pair is not of the form `[Name "value"]`.
+    MalformedTag { line: usize },
+    /// A `{` comment is never closed.
+    UnterminatedComment { line: usize },
+    /// The `FEN` tag does not describe a valid position.
+    InvalidFen(FenError),
+    /// A movetext token is not a legal move in its position.
+    IllegalMove { line: usize, san: String },
+    /// A `(` or `)` has no partner, or a variation has no move to replace.
+    UnbalancedVariation { line: usize },
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PgnError::MalformedTag { line } => write!(f, "line {}: malformed tag", line),
+            PgnError::UnterminatedComment { line } => {
+                write!(f, "line {}: unterminated comment", line)
+            }
+            PgnError::InvalidFen(e) => write!(f, "invalid FEN tag: {}", e),
+            PgnError::IllegalMove { line, san } => {
+                write!(f, "line {}: illegal or ambiguous move '{}'", line, san)
+            }
+            PgnError::UnbalancedVariation { line } => {
+                write!(f, "line {}: unbalanced variation", line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+impl PgnMove {
+    /// Wraps a move with no annotations.
+    pub const fn new(mv: Move) -> Self {
+        PgnMove {
+            mv,
+            nags: Vec::new(),
+            eval: None,
+            comment: None,
+            variations: Vec::new(),
+        }
+    }
+}
+
+impl PgnEval {
+    /// Builds the eval comment for a move the engine chose with `info`.
+    pub const fn from_search(info: &SearchInfo) -> Self {
+        PgnEval {
+            score: info.score,
+            depth: info.depth,
+            time_ms: Some(info.time_ms),
+        }
+    }
+}
+
+impl PgnGame {
+    /// Starts an empty game from `start` with the seven tag roster set to `?`.
+    ///
+    /// A non-standard start adds the `SetUp` and `FEN` tags.
+    pub fn new(start: Position) -> Self {
+        let mut game = PgnGame {
+            tags: SEVEN_TAG_ROSTER
+                .iter()
+                .map(|&name| (name.to_string(), "?".to_string()))
+                .collect(),
+            start,
+            comment: None,
+            moves: Vec::new(),
+            result: "*".to_string(),
+        };
+        game.set_tag("Result", "*");
+
+        if game.start.to_fen() != Position::new().to_fen() {
+            game.set_tag("SetUp", "1");
+            let fen = game.start.to_fen();
+            game.set_tag("FEN", &fen);
+        }
+
+        game
+    }
+
+    /// Returns the value of the first tag called `name`.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets `name` to `value`, replacing an existing tag or appending a new one.
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, existing)) => *existing = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Sets the game result marker and the matching `Result` tag.
+    pub fn set_result(&mut self, result: &str) {
+        self.result = result.to_string();
+        self.set_tag("Result", result);
+    }
+
+    /// Appends a main-line move, optionally with the engine eval behind it.
+    pub fn push(&mut self, mv: Move, eval: Option<PgnEval>) {
+        self.moves.push(PgnMove {
+            eval,
+            ..PgnMove::new(mv)
+        });
+    }
+
+    /// Returns the main-line moves without annotations.
+    pub fn mainline(&self) -> Vec<Move> {
+        self.moves.iter().map(|pgn_move| pgn_move.mv).collect()
+    }
+
+    /// Returns the position before each main-line move, followed by the final position.
+    pub fn positions(&self) -> Vec<Position> {
+        let mut pos = self.start.clone();
+        let mut positions = Vec::with_capacity(self.moves.len() + 1);
+        positions.push(pos.clone());
+
+        for pgn_move in &self.moves {
+            pos.make_move(pgn_move.mv);
+            positions.push(pos.clone());
+        }
+
+        positions
+    }
+
+    /// Returns the position after the last main-line move.
+    pub fn final_position(&self) -> Position {
+        let mut pos = self.start.clone();
+        for pgn_move in &self.moves {
+            pos.make_move(pgn_move.mv);
+        }
+        pos
+    }
+}
@@ -0,0 +1,481 @@
+use super::{PgnError, PgnEval, PgnGame, PgnMove};
+use crate::search::Score;
+use crate::Position;
+
+/// Iterator over the games of a PGN text.
+///
+/// A game that fails to parse yields its error and the reader resumes at the
+/// next game, so one corrupt record does not hide the rest of an archive.
+pub struct PgnReader<'a> {
+    lexer: Lexer<'a>,
+}
+
+impl<'a> PgnReader<'a> {
+    /// Reads games from `text`.
+    pub const fn new(text: &'a str) -> Self {
+        PgnReader {
+            lexer: Lexer::new(text),
+        }
+    }
+
+    fn read_game(&mut self) -> Result<PgnGame, PgnError> {
+        let mut tags = Vec::new();
+        while let Some(Token::Tag(name, value)) = self.lexer.peek()? {
+            tags.push((name, value));
+            self.lexer.next()?;
+        }
+
+        let start = match tags.iter().find(|(name, _)| name == "FEN") {
+            Some((_, fen)) => Position::from_fen(fen).map_err(PgnError::InvalidFen)?,
+            None => Position::new(),
+        };
+
+        let mut game = PgnGame {
+            tags,
+            start,
+            comment: None,
+            moves: Vec::new(),
+            result: "*".to_string(),
+        };
+
+        let mut pos = game.start.clone();
+        let mut leading_comment = None;
+        let end = self.read_line(&mut pos, &mut game.moves, &mut leading_comment, 0)?;
+        game.comment = leading_comment;
+        if let LineEnd::Result(result) = end {
+            game.result = result;
+        }
+
+        Ok(game)
+    }
+
+    /// Reads moves onto `moves` until the line ends, leaving `pos` as it was.
+    fn read_line(
+        &mut self,
+        pos: &mut Position,
+        moves: &mut Vec<PgnMove>,
+        leading_comment: &mut Option<String>,
+        depth: usize,
+    ) -> Result<LineEnd, PgnError> {
+        let end = self.read_line_moves(pos, moves, leading_comment, depth);
+
+        for pgn_move in moves.iter().rev() {
+            pos.unmake_move(pgn_move.mv);
+        }
+
+        end
+    }
+
+    fn read_line_moves(
+        &mut self,
+        pos: &mut Position,
+        moves: &mut Vec<PgnMove>,
+        leading_comment: &mut Option<String>,
+        depth: usize,
+    ) -> Result<LineEnd, PgnError> {
+        loop {
+            let line = self.lexer.line;
+            let token = match self.lexer.peek()? {
+                // a tag after movetext starts the next game when a result was omitted.
+                Some(Token::Tag(..)) if depth == 0 => return Ok(LineEnd::Eof),
+                None if depth == 0 => return Ok(LineEnd::Eof),
+                None => return Err(PgnError::UnbalancedVariation { line }),
+                Some(_) => self.lexer.next()?.expect("peeked token"),
+            };
+
+            match token {
+                Token::Tag(..) => return Err(PgnError::UnbalancedVariation { line }),
+                Token::Comment(text) => match moves.last_mut() {
+                    Some(last) => attach_comment(last, text),
+                    None => append_text(leading_comment, text),
+                },
+                Token::Nag(nag) => {
+                    if let Some(last) = moves.last_mut() {
+                        last.nags.push(nag);
+                    }
+                }
+                Token::OpenVariation => {
+                    let Some(last) = moves.last() else {
+                        return Err(PgnError::UnbalancedVariation { line });
+                    };
+                    let replaced = last.mv;
+
+                    // a variation replaces the last move, so read it from the position before.
+                    pos.unmake_move(replaced);
+                    let mut variation = Vec::new();
+                    let mut variation_comment = None;
+                    let end =
+                        self.read_line(pos, &mut variation, &mut variation_comment, depth + 1);
+                    pos.make_move(replaced);
+
+                    if end? != LineEnd::CloseVariation {
+                        return Err(PgnError::UnbalancedVariation { line });
+                    }
+                    if let (Some(text), Some(first)) = (variation_comment, variation.first_mut()) {
+                        first.comment = Some(match first.comment.take() {
+                            Some(existing) => format!("{} {}", text, existing),
+                            None => text,
+                        });
+                    }
+                    if !variation.is_empty() {
+                        moves
+                            .last_mut()
+                            .expect("replaced move")
+                            .variations
+                            .push(variation);
+                    }
+                }
+                Token::CloseVariation if depth > 0 => return Ok(LineEnd::CloseVariation),
+                Token::CloseVariation => return Err(PgnError::UnbalancedVariation { line }),
+                Token::Result(result) if depth == 0 => return Ok(LineEnd::Result(result)),
+                Token::Result(_) => return Err(PgnError::UnbalancedVariation { line }),
+                Token::Move(san, nag) => {
+                    let mv = pos
+                        .parse_san(&san)
+                        .ok_or(PgnError::IllegalMove { line, san })?;
+                    pos.make_move(mv);
+
+                    let mut pgn_move = PgnMove::new(mv);
+                    pgn_move.nags.extend(nag);
+                    moves.push(pgn_move);
+                }
+            }
+        }
+    }
+
+    /// Skips the rest of a game that failed to parse.
+    fn recover(&mut self) {
+        loop {
+            match self.lexer.next() {
+                Ok(None) | Ok(Some(Token::Result(_))) => return,
+                Ok(Some(_)) => {}
+                Err(_) => self.lexer.skip_char(),
+            }
+        }
+    }
+}
+
+impl Iterator for PgnReader<'_> {
+    type Item = Result<PgnGame, PgnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.lexer.peek() {
+            Ok(None) => return None,
+            Ok(Some(_)) => {}
+            Err(e) => {
+                self.recover();
+                return Some(Err(e));
+            }
+        }
+
+        let game = self.read_game();
+        if game.is_err() {
+            self.recover();
+        }
+        Some(game)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum LineEnd {
+    Result(String),
+    CloseVariation,
+    Eof,
+}
+
+#[derive(Clone, Debug)]
+enum Token {
+    Tag(String, String),
+    Comment(String),
+    Nag(u8),
+    OpenVariation,
+    CloseVariation,
+    Result(String),
+    /// A SAN token with the NAG of its `!`/`?` suffix, if any.
+    Move(String, Option<u8>),
+}
+
+struct Lexer<'a> {
+    text: &'a str,
+    offset: usize,
+    line: usize,
+    peeked: Option<Token>,
+}
+
+impl<'a> Lexer<'a> {
+    const fn new(text: &'a str) -> Self {
+        Lexer {
+            text,
+            offset: 0,
+            line: 1,
+            peeked: None,
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<Token>, PgnError> {
+        if self.peeked.is_none() {
+            self.peeked = self.lex()?;
+        }
+        Ok(self.peeked.clone())
+    }
+
+    fn next(&mut self) -> Result<Option<Token>, PgnError> {
+        match self.peeked.take() {
+            Some(token) => Ok(Some(token)),
+            None => self.lex(),
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.offset..]
+    }
+
+    fn advance(&mut self, bytes: usize) -> &'a str {
+        let taken = &self.text[self.offset..self.offset + bytes];
+        self.line += taken.matches('\n').count();
+        self.offset += bytes;
+        taken
+    }
+
+    fn skip_char(&mut self) {
+        self.peeked = None;
+        if let Some(c) = self.rest().chars().next() {
+            self.advance(c.len_utf8());
+        }
+    }
+
+    fn skip_whitespace_and_escapes(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.advance(rest.len() - trimmed.len());
+
+            // escape lines: `%` in the first column hides the rest of the line.
+            let at_line_start = self.offset == 0 || self.text[..self.offset].ends_with('\n');
+            if at_line_start && self.rest().starts_with('%') {
+                let len = self.rest().find('\n').unwrap_or(self.rest().len());
+                self.advance(len);
+                continue;
+            }
+
+            return;
+        }
+    }
+
+    fn lex(&mut self) -> Result<Option<Token>, PgnError> {
+        loop {
+            self.skip_whitespace_and_escapes();
+            let line = self.line;
+            let Some(c) = self.rest().chars().next() else {
+                return Ok(None);
+            };
+
+            return Ok(Some(match c {
+                '[' => self.lex_tag()?,
+                '{' => {
+                    let Some(end) = self.rest().find('}') else {
+                        return Err(PgnError::UnterminatedComment { line });
+                    };
+                    let text = self.advance(end + 1);
+                    Token::Comment(
+                        text[1..end]
+                            .split_whitespace()
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                    )
+                }
+                ';' => {
+                    let len = self.rest().find('\n').unwrap_or(self.rest().len());
+                    let text = self.advance(len);
+                    Token::Comment(text[1..].trim().to_string())
+                }
+                '(' => {
+                    self.advance(1);
+                    Token::OpenVariation
+                }
+                ')' => {
+                    self.advance(1);
+                    Token::CloseVariation
+                }
+                '$' => {
+                    let digits = self.rest()[1..]
+                        .find(|c: char| !c.is_ascii_digit())
+                        .unwrap_or(self.rest().len() - 1);
+                    let text = self.advance(digits + 1);
+                    match text[1..].parse::<u8>() {
+                        Ok(nag) => Token::Nag(nag),
+                        Err(_) => continue,
+                    }
+                }
+                _ => {
+                    let len = self
+                        .rest()
+                        .find(|c: char| c.is_whitespace() || "{}()[];$".contains(c))
+                        .unwrap_or(self.rest().len());
+                    let symbol = self.advance(len.max(c.len_utf8()));
+                    match lex_symbol(symbol) {
+                        Some(token) => token,
+                        None => continue,
+                    }
+                }
+            }));
+        }
+    }
+
+    fn lex_tag(&mut self) -> Result<Token, PgnError> {
+        let line = self.line;
+        let malformed = PgnError::MalformedTag { line };
+        let end_of_line = self.rest().find('\n').unwrap_or(self.rest().len());
+        let tag_line = &self.rest()[..end_of_line];
+
+        let inner = tag_line[1..].trim_start();
+        let name_len = inner
+            .find(|c: char| c.is_whitespace() || c == '"')
+            .ok_or(malformed.clone())?;
+        let name = &inner[..name_len];
+        let value_part = inner[name_len..].trim_start();
+        let value_part = value_part.strip_prefix('"').ok_or(malformed.clone())?;
+
+        let mut value = String::new();
+        let mut chars = value_part.char_indices();
+        let close = loop {
+            match chars.next() {
+                Some((_, '\\')) => {
+                    if let Some((_, escaped)) = chars.next() {
+                        value.push(escaped);
+                    }
+                }
+                Some((i, '"')) => break i,
+                Some((_, c)) => value.push(c),
+                None => return Err(malformed),
+            }
+        };
+
+        let after = value_part[close + 1..].trim_start();
+        if name.is_empty() || !after.starts_with(']') {
+            return Err(malformed);
+        }
+
+        let consumed = tag_line.len() - after.len() + 1;
+        self.advance(consumed);
+        Ok(Token::Tag(name.to_string(), value))
+    }
+}
+
+/// Classifies a bare movetext symbol; move numbers yield `None`.
+fn lex_symbol(symbol: &str) -> Option<Token> {
+    if matches!(symbol, "1-0" | "0-1" | "1/2-1/2" | "*") {
+        return Some(Token::Result(symbol.to_string()));
+    }
+
+    // move numbers: `12.`, `12...` and `12.e4` all drop the number.
+    let digits = symbol
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(symbol.len());
+    let san = if digits > 0 && symbol[digits..].starts_with('.') {
+        symbol[digits..].trim_start_matches('.')
+    } else {
+        symbol
+    };
+    if san.is_empty() {
+        return None;
+    }
+
+    let body = san.trim_end_matches(['!', '?']);
+    let nag = match &san[body.len()..] {
+        "!" => Some(1),
+        "?" => Some(2),
+        "!!" => Some(3),
+        "??" => Some(4),
+        "!?" => Some(5),
+        "?!" => Some(6),
+        _ => None,
+    };
+
+    Some(Token::Move(body.to_string(), nag))
+}
+
+fn append_text(slot: &mut Option<String>, text: String) {
+    *slot = Some(match slot.take() {
+        Some(existing) => format!("{} {}", existing, text),
+        None => text,
+    });
+}
+
+/// Stores a move comment, decoding a leading engine eval on the first one.
+fn attach_comment(pgn_move: &mut PgnMove, text: String) {
+    if pgn_move.eval.is_none() && pgn_move.comment.is_none() {
+        if let Some((eval, rest)) = parse_eval_comment(&text) {
+            pgn_move.eval = Some(eval);
+            if !rest.is_empty() {
+                pgn_move.comment = Some(rest.to_string());
+            }
+            return;
+        }
+    }
+
+    append_text(&mut pgn_move.comment, text);
+}
+
+/// Decodes `+0.27/4 0.084s`, `-M3/12 1.5s` or `0.00/50` and returns the remaining text.
+fn parse_eval_comment(text: &str) -> Option<(PgnEval, &str)> {
+    let (eval, rest) = match text.split_once(',') {
+        Some((eval, rest)) => (eval, rest.trim()),
+        None => (text, ""),
+    };
+
+    let mut parts = eval.split_whitespace();
+    let (score_text, depth_text) = parts.next()?.split_once('/')?;
+    let time_ms = match parts.next() {
+        Some(time) => Some(parse_seconds(time)?),
+        None => None,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let score = parse_score(score_text)?;
+    let depth = depth_text.parse::<u8>().ok()?;
+
+    Some((
+        PgnEval {
+            score,
+            depth,
+            time_ms,
+        },
+        rest,
+    ))
+}
+
+fn parse_score(text: &str) -> Option<Score> {
+    let (negative, unsigned) = match text.as_bytes().first()? {
+        b'-' => (true, &text[1..]),
+        b'+' => (false, &text[1..]),
+        _ => (false, text),
+    };
+    let sign = if negative { -1 } else { 1 };
+
+    if let Some(moves) = unsigned.strip_prefix('M') {
+        return Some(Score::Mate(sign * moves.parse::<i32>().ok()?));
+    }
+
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, "0"));
+    if fraction.is_empty() || fraction.len() > 2 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let whole = whole.parse::<i32>().ok()?;
+    let fraction = fraction.parse::<i32>().ok()? * if fraction.len() == 1 { 10 } else { 1 };
+
+    Some(Score::Cp(sign * (whole * 100 + fraction)))
+}
+
+fn parse_seconds(text: &str) -> Option<u64> {
+    let seconds = text.strip_suffix('s')?;
+    let (whole, fraction) = seconds.split_once('.').unwrap_or((seconds, ""));
+    if fraction.len() > 3 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let millis = format!("{:0<3}", fraction).parse::<u64>().ok()?;
+    Some(whole.parse::<u64>().ok()? * 1000 + millis)
+}
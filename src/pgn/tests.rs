@@ -0,0 +1,179 @@
+use super::*;
+use crate::search::Score;
+
+const ANNOTATED: &str = r#"[Event "Annotated"]
+[Site "?"]
+[Date "2026.04.01"]
+[Round "1"]
+[White "Alpha \"A\""]
+[Black "Beta"]
+[Result "1-0"]
+
+{Opening comment} 1. e4 e5 2. Nf3!? {A main line} Nc6 (2... d6 3. d4 (3. Bc4) 3...
+exd4) 3. Bb5 $1 a6 ; rest-of-line comment
+4. Ba4 {+0.35/12 1.5s} Nf6 {-0.20/11 0.9s, still theory} 1-0
+"#;
+
+#[test]
+fn reads_tags_comments_nags_and_variations() {
+    let games: Vec<_> = PgnReader::new(ANNOTATED).collect();
+    assert_eq!(games.len(), 1);
+    let game = games[0].as_ref().expect("valid game");
+
+    assert_eq!(game.tag("White"), Some("Alpha \"A\""));
+    assert_eq!(game.result, "1-0");
+    assert_eq!(game.comment.as_deref(), Some("Opening comment"));
+
+    let sans: Vec<_> = {
+        let mut pos = game.start.clone();
+        game.mainline()
+            .into_iter()
+            .map(|mv| {
+                let san = pos.move_to_san(mv);
+                pos.make_move(mv);
+                san
+            })
+            .collect()
+    };
+    assert_eq!(sans, ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Ba4", "Nf6"]);
+
+    let nf3 = &game.moves[2];
+    assert_eq!(nf3.nags, [5]);
+    assert_eq!(nf3.comment.as_deref(), Some("A main line"));
+
+    let nc6 = &game.moves[3];
+    assert_eq!(nc6.variations.len(), 1);
+    let variation = &nc6.variations[0];
+    assert_eq!(variation.len(), 3);
+    assert_eq!(variation[1].variations.len(), 1);
+
+    assert_eq!(game.moves[4].nags, [1]);
+    assert_eq!(
+        game.moves[5].comment.as_deref(),
+        Some("rest-of-line comment")
+    );
+
+    let ba4 = &game.moves[6];
+    assert_eq!(
+        ba4.eval,
+        Some(PgnEval {
+            score: Score::Cp(35),
+            depth: 12,
+            time_ms: Some(1500),
+        })
+    );
+    assert_eq!(ba4.comment, None);
+
+    let nf6 = &game.moves[7];
+    assert_eq!(nf6.eval.map(|eval| eval.score), Some(Score::Cp(-20)));
+    assert_eq!(nf6.comment.as_deref(), Some("still theory"));
+}
+
+#[test]
+fn written_games_read_back_identically() {
+    let game = PgnReader::new(ANNOTATED)
+        .next()
+        .expect("one game")
+        .expect("valid game");
+    let written = game.to_pgn();
+    let reread = PgnReader::new(&written)
+        .next()
+        .expect("one game")
+        .expect("valid game");
+
+    assert_eq!(reread.tags, game.tags);
+    assert_eq!(reread.comment, game.comment);
+    assert_eq!(reread.moves, game.moves);
+    assert_eq!(reread.result, game.result);
+    assert!(written.lines().all(|line| line.len() < 80));
+    assert!(written
+        .replace('\n', " ")
+        .contains("2... Nc6 (2... d6 3. d4 (3. Bc4) 3... exd4) 3. Bb5 $1"));
+}
+
+#[test]
+fn records_engine_games_with_eval_comments() {
+    let start = Position::from_fen("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1").expect("valid FEN");
+    let mut game = PgnGame::new(start);
+    let mut pos = game.start.clone();
+
+    for (san, eval) in [
+        ("Kd2", None),
+        (
+            "Kd7",
+            Some(PgnEval {
+                score: Score::Cp(-510),
+                depth: 9,
+                time_ms: Some(84),
+            }),
+        ),
+        (
+            "Ra8",
+            Some(PgnEval {
+                score: Score::Mate(7),
+                depth: 20,
+                time_ms: None,
+            }),
+        ),
+    ] {
+        let mv = pos.parse_san(san).expect("legal move");
+        game.push(mv, eval);
+        pos.make_move(mv);
+    }
+    game.set_result("*");
+
+    let written = game.to_pgn();
+    assert!(written.contains("[SetUp \"1\"]"));
+    assert!(written.contains("[FEN \"4k3/8/8/8/8/8/8/R3K3 w Q - 0 1\"]"));
+    assert!(written.contains("1. Kd2 Kd7 {-5.10/9 0.084s} 2. Ra8 {+M7/20} *"));
+
+    let reread = PgnReader::new(&written)
+        .next()
+        .expect("one game")
+        .expect("valid game");
+    assert_eq!(reread.moves, game.moves);
+    assert_eq!(reread.final_position().hash(), pos.hash());
+}
+
+#[test]
+fn reports_bad_games_and_resumes_at_the_next() {
+    let text = "[Event \"bad\"]\n\n1. e4 e5 2. Ke3 *\n\n[Event \"good\"]\n\n1. d4 d5 1/2-1/2\n\n\
+                [Event \"unbalanced\"]\n\n1. c4 (1. Nf3 *\n";
+    let games: Vec<_> = PgnReader::new(text).collect();
+
+    assert_eq!(games.len(), 3);
+    assert_eq!(
+        games[0].as_ref().err(),
+        Some(&PgnError::IllegalMove {
+            line: 3,
+            san: "Ke3".to_string()
+        })
+    );
+    let good = games[1].as_ref().expect("valid game");
+    assert_eq!(good.tag("Event"), Some("good"));
+    assert_eq!(good.moves.len(), 2);
+    assert_eq!(good.result, "1/2-1/2");
+    assert!(matches!(
+        games[2],
+        Err(PgnError::UnbalancedVariation { .. })
+    ));
+}
+
+#[test]
+fn reads_the_match_archive() {
+    let archive = include_str!("../../archive/data/results/ablation_pesto_add.pgn");
+    let mut count = 0;
+
+    for game in PgnReader::new(archive) {
+        let game = game.expect("archive game parses");
+        let plies = game
+            .tag("PlyCount")
+            .and_then(|plies| plies.parse::<usize>().ok())
+            .expect("PlyCount tag");
+        assert_eq!(game.moves.len(), plies);
+        assert_eq!(game.tag("Result"), Some(game.result.as_str()));
+        count += 1;
+    }
+
+    assert_eq!(count, 800);
+}
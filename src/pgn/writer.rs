@@ -0,0 +1,130 @@
+use super::{PgnEval, PgnGame, PgnMove};
+use crate::search::Score;
+use crate::{Color, Position};
+use std::fmt;
+
+/// Export format keeps movetext lines below 80 columns.
+const MAX_LINE_LEN: usize = 79;
+
+impl PgnGame {
+    /// Writes the game in PGN export format.
+    pub fn to_pgn(&self) -> String {
+        let mut out = String::new();
+
+        for (name, value) in &self.tags {
+            let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+            out.push_str(&format!("[{} \"{}\"]\n", name, escaped));
+        }
+        out.push('\n');
+
+        let mut tokens = Vec::new();
+        if let Some(comment) = &self.comment {
+            tokens.push(format!("{{{}}}", sanitize_comment(comment)));
+        }
+        let mut pos = self.start.clone();
+        write_line(&mut pos, &self.moves, &mut tokens);
+        tokens.push(self.result.clone());
+
+        let mut line_len = 0;
+        let mut previous = "";
+        for token in &tokens {
+            // variation parentheses hug their first and last moves: `(2... d6 3. d4)`.
+            let separator = usize::from(previous != "(" && token != ")");
+            if line_len > 0 && line_len + separator + token.len() > MAX_LINE_LEN {
+                out.push('\n');
+                line_len = 0;
+            } else if line_len > 0 && separator == 1 {
+                out.push(' ');
+                line_len += 1;
+            }
+            line_len += token.len();
+            out.push_str(token);
+            previous = token;
+        }
+        out.push_str("\n\n");
+
+        out
+    }
+}
+
+impl fmt::Display for PgnGame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_pgn())
+    }
+}
+
+/// Emits the tokens of `moves` played from `pos`, leaving `pos` unchanged.
+fn write_line(pos: &mut Position, moves: &[PgnMove], tokens: &mut Vec<String>) {
+    // black moves need an explicit `N...` number at the start of a line and after an interruption.
+    let mut needs_number = true;
+
+    for pgn_move in moves {
+        let number = pos.fullmove;
+        match pos.side_to_move {
+            Color::White => tokens.push(format!("{}.", number)),
+            Color::Black if needs_number => tokens.push(format!("{}...", number)),
+            Color::Black => {}
+        }
+
+        tokens.push(pos.move_to_san(pgn_move.mv));
+        tokens.extend(pgn_move.nags.iter().map(|nag| format!("${}", nag)));
+        needs_number = false;
+
+        let comment = match (&pgn_move.eval, &pgn_move.comment) {
+            (Some(eval), Some(text)) => Some(format!("{}, {}", format_eval(eval), text)),
+            (Some(eval), None) => Some(format_eval(eval)),
+            (None, Some(text)) => Some(text.clone()),
+            (None, None) => None,
+        };
+        if let Some(comment) = comment {
+            tokens.push(format!("{{{}}}", sanitize_comment(&comment)));
+            needs_number = true;
+        }
+
+        for variation in &pgn_move.variations {
+            tokens.push("(".to_string());
+            write_line(pos, variation, tokens);
+            tokens.push(")".to_string());
+            needs_number = true;
+        }
+
+        pos.make_move(pgn_move.mv);
+    }
+
+    for pgn_move in moves.iter().rev() {
+        pos.unmake_move(pgn_move.mv);
+    }
+}
+
+/// Formats an eval in the `+0.27/4 0.084s` form match runners write.
+fn format_eval(eval: &PgnEval) -> String {
+    let score = match eval.score {
+        Score::Cp(0) => "0.00".to_string(),
+        Score::Cp(cp) => {
+            let sign = if cp < 0 { '-' } else { '+' };
+            format!("{}{}.{:02}", sign, cp.abs() / 100, cp.abs() % 100)
+        }
+        Score::Mate(moves) if moves < 0 => format!("-M{}", -moves),
+        Score::Mate(moves) => format!("+M{}", moves),
+    };
+
+    match eval.time_ms {
+        Some(ms) => format!("{}/{} {}", score, eval.depth, format_seconds(ms)),
+        None => format!("{}/{}", score, eval.depth),
+    }
+}
+
+fn format_seconds(ms: u64) -> String {
+    let fraction = format!("{:03}", ms % 1000);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        format!("{}s", ms / 1000)
+    } else {
+        format!("{}.{}s", ms / 1000, fraction)
+    }
+}
+
+/// PGN comments cannot contain a closing brace.
+fn sanitize_comment(comment: &str) -> String {
+    comment.replace('}', ")")
+}
@@ -1,16 +1,21 @@
 //! Position state and state transitions.
 //!
 //! `Position` owns the engine's authoritative board representation together with
-//! hashing, repetition history, FEN parsing, and make/unmake support.
+//! hashing, repetition history, FEN parsing and validation, SAN notation,
+//! castling geometry (including Chess960), and make/unmake support.
 
+mod castling;
 mod draw;
 mod fen;
 mod hash;
 mod make;
+mod san;
 mod state;
 #[cfg(test)]
 mod tests;
 mod unmake;
+mod validate;
 
 /// Public position types exported by the position subsystem.
+pub use fen::FenError;
 pub use state::{GameState, Position};
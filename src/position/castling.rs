@@ -0,0 +1,91 @@
+use super::Position;
+use crate::types::{CastleRights, Color, Move, MoveType, Piece};
+
+/// King destination file for kingside and queenside castling.
+const KING_TARGET_FILES: [usize; 2] = [6, 2];
+/// Rook destination file for kingside and queenside castling.
+const ROOK_TARGET_FILES: [usize; 2] = [5, 3];
+
+#[inline(always)]
+const fn back_rank(color: Color) -> usize {
+    match color {
+        Color::White => 0,
+        Color::Black => 56,
+    }
+}
+
+#[inline(always)]
+const fn side_index(kingside: bool) -> usize {
+    if kingside {
+        0
+    } else {
+        1
+    }
+}
+
+impl Position {
+    /// Grants the castling right for `color` with the rook on `rook_sq`.
+    ///
+    /// The side is derived from the rook's file relative to the king, so this
+    /// handles standard, X-FEN and Shredder-FEN rights alike. Switches the
+    /// position into Chess960 mode when the king or rook is off its classical
+    /// square.
+    pub(crate) fn add_castling_right(&mut self, color: Color, rook_sq: usize) {
+        let king_sq = self.pieces[Piece::King as usize].0 & self.colors[color as usize].0;
+        let king_sq = king_sq.trailing_zeros() as usize;
+        let kingside = rook_sq > king_sq;
+        let right = CastleRights::right(color, kingside);
+
+        self.castling_rights.0 |= right;
+        self.castling_rooks[CastleRights::index(color, kingside)] = rook_sq as u8;
+        self.castling_masks[king_sq] |= right;
+        self.castling_masks[rook_sq] |= right;
+
+        let base = back_rank(color);
+        let classical_rook = base + if kingside { 7 } else { 0 };
+        if king_sq != base + 4 || rook_sq != classical_rook {
+            self.chess960 = true;
+        }
+    }
+
+    /// Enables or disables Chess960 move notation (UCI `UCI_Chess960`).
+    pub fn set_chess960(&mut self, chess960: bool) {
+        self.chess960 = chess960;
+    }
+
+    /// Returns the starting square of the castling rook for `color` on one side.
+    #[inline(always)]
+    pub fn castling_rook(&self, color: Color, kingside: bool) -> usize {
+        self.castling_rooks[CastleRights::index(color, kingside)] as usize
+    }
+
+    /// Returns the king destination square for castling, independent of the start files.
+    #[inline(always)]
+    pub const fn castling_king_target(color: Color, kingside: bool) -> usize {
+        back_rank(color) + KING_TARGET_FILES[side_index(kingside)]
+    }
+
+    /// Returns the rook's `(from, to)` squares for a castling move whose king lands on `king_to`.
+    #[inline(always)]
+    pub fn castling_rook_move(&self, king_to: usize) -> (usize, usize) {
+        let color = if king_to < 8 {
+            Color::White
+        } else {
+            Color::Black
+        };
+        let kingside = king_to % 8 == KING_TARGET_FILES[0];
+        let rook_to = back_rank(color) + ROOK_TARGET_FILES[side_index(kingside)];
+
+        (self.castling_rook(color, kingside), rook_to)
+    }
+
+    /// Formats `mv` in UCI notation, writing castling as king-takes-rook in Chess960 mode.
+    pub fn move_to_uci(&self, mv: Move) -> String {
+        if self.chess960 && mv.move_type() == MoveType::Castle {
+            let (rook_from, _) = self.castling_rook_move(mv.to());
+            return Move::new(mv.from(), rook_from, MoveType::Castle).to_uci();
+        }
+
+        mv.to_uci()
+    }
+}
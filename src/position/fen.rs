@@ -1,16 +1,92 @@
 use super::Position;
 use crate::types::{Bitboard, CastleRights, Color, Piece};
+use std::fmt;
+
+/// Reasons a FEN string is rejected, naming the offending field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FenError {
+    /// A required field is absent.
+    MissingField(&'static str),
+    /// The board field does not have exactly eight ranks.
+    RankCount(usize),
+    /// A rank (1-8) describes more or fewer than eight squares.
+    RankLength { rank: u8, squares: usize },
+    /// A board character is neither a piece letter nor a digit 1-8.
+    InvalidPiece { rank: u8, piece: char },
+    /// The side-to-move field is not `w` or `b`.
+    InvalidSideToMove(String),
+    /// A castling character has no matching king and rook.
+    InvalidCastling(char),
+    /// The en passant field is not `-` or a square on the third or sixth rank.
+    InvalidEnPassant(String),
+    /// The halfmove clock is not a number.
+    InvalidHalfmove(String),
+    /// The fullmove number is not a positive number.
+    InvalidFullmove(String),
+    /// A side does not have exactly one king.
+    KingCount { color: Color, count: u32 },
+    /// A pawn stands on the first or eighth rank.
+    PawnOnBackRank(u8),
+    /// The side that just moved is still in check.
+    OpponentInCheck,
+    /// The en passant square does not follow a double pawn push.
+    ImpossibleEnPassant(u8),
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FenError::MissingField(field) => write!(f, "missing {} field", field),
+            FenError::RankCount(count) => write!(f, "board has {} ranks, expected 8", count),
+            FenError::RankLength { rank, squares } => {
+                write!(f, "rank {} has {} squares, expected 8", rank, squares)
+            }
+            FenError::InvalidPiece { rank, piece } => {
+                write!(f, "invalid piece '{}' on rank {}", piece, rank)
+            }
+            FenError::InvalidSideToMove(side) => write!(f, "invalid side to move '{}'", side),
+            FenError::InvalidCastling(c) => write!(f, "castling right '{}' has no rook", c),
+            FenError::InvalidEnPassant(ep) => write!(f, "invalid en passant square '{}'", ep),
+            FenError::InvalidHalfmove(clock) => write!(f, "invalid halfmove clock '{}'", clock),
+            FenError::InvalidFullmove(number) => {
+                write!(f, "invalid fullmove number '{}'", number)
+            }
+            FenError::KingCount { color, count } => {
+                write!(f, "{:?} has {} kings, expected 1", color, count)
+            }
+            FenError::PawnOnBackRank(sq) => write!(f, "pawn on back rank at {}", square_name(*sq)),
+            FenError::OpponentInCheck => write!(f, "side not to move is in check"),
+            FenError::ImpossibleEnPassant(sq) => {
+                write!(
+                    f,
+                    "en passant square {} has no double-pushed pawn",
+                    square_name(*sq)
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
 
 impl Position {
     /// Builds a position from a FEN string.
     ///
     /// The parser accepts the standard six-field FEN form. If the halfmove or
     /// fullmove counters are omitted, they default to `0` and `1` respectively.
-    pub fn from_fen(fen: &str) -> Result<Self, &'static str> {
-        let parts: Vec<&str> = fen.split_whitespace().collect();
-        if parts.len() < 4 {
-            return Err("Invalid FEN");
-        }
+    ///
+    /// Castling rights may be given as `KQkq`, as X-FEN (where `K`/`Q` name the
+    /// outermost rook on that side of the king), or as Shredder-FEN rook files
+    /// such as `HAha`. Any non-classical king or rook square enables Chess960.
+    ///
+    /// The parsed position is checked with [`Position::validate`], so a
+    /// successful parse is always safe to search.
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        let mut parts = fen.split_whitespace();
+        let board = parts.next().ok_or(FenError::MissingField("board"))?;
+        let side = parts.next().ok_or(FenError::MissingField("side to move"))?;
+        let castling = parts.next().ok_or(FenError::MissingField("castling"))?;
+        let en_passant = parts.next().ok_or(FenError::MissingField("en passant"))?;
 
         let mut pos = Position {
             pieces: [Bitboard::new(); 6],
@@ -18,73 +94,246 @@ impl Position {
             board: [None; 64],
             side_to_move: Color::White,
             castling_rights: CastleRights::NONE,
+            castling_rooks: [0; 4],
+            castling_masks: [0; 64],
+            chess960: false,
             en_passant: None,
             halfmove: 0,
             fullmove: 1,
             hash: 0,
+            pawn_hash: 0,
             history: Vec::with_capacity(1024),
         };
 
-        let mut sq = 56;
-        for c in parts[0].chars() {
-            match c {
-                '/' => sq -= 16,
-                '1'..='8' => sq += c.to_digit(10).unwrap() as usize,
-                _ => {
-                    let (piece, color) = match c {
-                        'P' => (Piece::Pawn, Color::White),
-                        'N' => (Piece::Knight, Color::White),
-                        'B' => (Piece::Bishop, Color::White),
-                        'R' => (Piece::Rook, Color::White),
-                        'Q' => (Piece::Queen, Color::White),
-                        'K' => (Piece::King, Color::White),
-                        'p' => (Piece::Pawn, Color::Black),
-                        'n' => (Piece::Knight, Color::Black),
-                        'b' => (Piece::Bishop, Color::Black),
-                        'r' => (Piece::Rook, Color::Black),
-                        'q' => (Piece::Queen, Color::Black),
-                        'k' => (Piece::King, Color::Black),
-                        _ => return Err("Invalid piece"),
-                    };
-                    pos.add_piece(sq, color, piece);
-                    sq += 1;
+        let ranks: Vec<&str> = board.split('/').collect();
+        if ranks.len() != 8 {
+            return Err(FenError::RankCount(ranks.len()));
+        }
+
+        for (row, rank_str) in ranks.iter().enumerate() {
+            let rank = 7 - row;
+            let mut file = 0;
+            for c in rank_str.chars() {
+                if let Some(skip) = c.to_digit(10).filter(|d| (1..=8).contains(d)) {
+                    file += skip as usize;
+                    continue;
+                }
+
+                let piece = match c.to_ascii_lowercase() {
+                    'p' => Piece::Pawn,
+                    'n' => Piece::Knight,
+                    'b' => Piece::Bishop,
+                    'r' => Piece::Rook,
+                    'q' => Piece::Queen,
+                    'k' => Piece::King,
+                    _ => {
+                        return Err(FenError::InvalidPiece {
+                            rank: rank as u8 + 1,
+                            piece: c,
+                        })
+                    }
+                };
+                let color = if c.is_ascii_uppercase() {
+                    Color::White
+                } else {
+                    Color::Black
+                };
+
+                if file < 8 {
+                    pos.add_piece(rank * 8 + file, color, piece);
                 }
+                file += 1;
+            }
+
+            if file != 8 {
+                return Err(FenError::RankLength {
+                    rank: rank as u8 + 1,
+                    squares: file,
+                });
             }
         }
 
-        pos.side_to_move = match parts[1] {
+        // castling rights are resolved against the king, so check kings first.
+        pos.validate_kings()?;
+
+        pos.side_to_move = match side {
             "w" => Color::White,
             "b" => Color::Black,
-            _ => return Err("Invalid side to move"),
+            _ => return Err(FenError::InvalidSideToMove(side.to_string())),
         };
 
-        let mut rights = 0u8;
-        for c in parts[2].chars() {
-            rights |= match c {
-                'K' => 1,
-                'Q' => 2,
-                'k' => 4,
-                'q' => 8,
-                '-' => 0,
-                _ => return Err("Invalid castling"),
-            };
+        if castling != "-" {
+            for c in castling.chars() {
+                let color = if c.is_ascii_uppercase() {
+                    Color::White
+                } else {
+                    Color::Black
+                };
+                let rook_sq = pos
+                    .castling_rook_square(color, c.to_ascii_lowercase())
+                    .ok_or(FenError::InvalidCastling(c))?;
+                pos.add_castling_right(color, rook_sq);
+            }
         }
-        pos.castling_rights = CastleRights(rights);
 
-        if parts[3] != "-" {
-            let file = parts[3].as_bytes()[0] - b'a';
-            let rank = parts[3].as_bytes()[1] - b'1';
-            pos.en_passant = Some(rank * 8 + file);
+        if en_passant != "-" {
+            pos.en_passant = Some(
+                parse_square(en_passant)
+                    .filter(|sq| matches!(sq / 8, 2 | 5))
+                    .ok_or_else(|| FenError::InvalidEnPassant(en_passant.to_string()))?,
+            );
         }
 
-        if parts.len() >= 5 {
-            pos.halfmove = parts[4].parse().unwrap_or(0);
+        if let Some(halfmove) = parts.next() {
+            pos.halfmove = halfmove
+                .parse()
+                .map_err(|_| FenError::InvalidHalfmove(halfmove.to_string()))?;
         }
-        if parts.len() >= 6 {
-            pos.fullmove = parts[5].parse().unwrap_or(1);
+        if let Some(fullmove) = parts.next() {
+            pos.fullmove = fullmove
+                .parse()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| FenError::InvalidFullmove(fullmove.to_string()))?;
         }
 
+        pos.validate()?;
         pos.hash = pos.compute_hash();
+        pos.pawn_hash = pos.compute_pawn_hash();
         Ok(pos)
     }
+
+    /// Serializes the position as a six-field FEN string.
+    ///
+    /// Castling is written as `KQkq` for standard chess and as Shredder-FEN rook
+    /// files in Chess960 mode, so `from_fen` restores the same rights and rooks.
+    /// The en passant square is written whenever one is set, matching what
+    /// `from_fen` accepts and what the hash includes.
+    pub fn to_fen(&self) -> String {
+        let mut fen = String::with_capacity(90);
+
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                match self.board[rank * 8 + file] {
+                    Some((piece, color)) => {
+                        if empty > 0 {
+                            fen.push((b'0' + empty) as char);
+                            empty = 0;
+                        }
+                        fen.push(piece_char(piece, color));
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                fen.push((b'0' + empty) as char);
+            }
+            if rank > 0 {
+                fen.push('/');
+            }
+        }
+
+        fen.push(' ');
+        fen.push(match self.side_to_move {
+            Color::White => 'w',
+            Color::Black => 'b',
+        });
+
+        fen.push(' ');
+        let castling_start = fen.len();
+        for color in [Color::White, Color::Black] {
+            for kingside in [true, false] {
+                if self.castling_rights.0 & CastleRights::right(color, kingside) == 0 {
+                    continue;
+                }
+
+                let c = if self.chess960 {
+                    (b'a' + (self.castling_rook(color, kingside) % 8) as u8) as char
+                } else if kingside {
+                    'k'
+                } else {
+                    'q'
+                };
+                fen.push(match color {
+                    Color::White => c.to_ascii_uppercase(),
+                    Color::Black => c,
+                });
+            }
+        }
+        if fen.len() == castling_start {
+            fen.push('-');
+        }
+
+        fen.push(' ');
+        match self.en_passant {
+            Some(sq) => fen.push_str(&square_name(sq)),
+            None => fen.push('-'),
+        }
+
+        fen.push_str(&format!(" {} {}", self.halfmove, self.fullmove));
+        fen
+    }
+
+    /// Resolves one castling-field character to the rook square it refers to.
+    ///
+    /// `k`/`q` pick the outermost rook on that side of the king; `a`-`h` name
+    /// the rook's file directly. Returns `None` when no matching rook exists.
+    fn castling_rook_square(&self, color: Color, c: char) -> Option<usize> {
+        let rank = if color == Color::White { 0 } else { 56 };
+        let king_sq =
+            (self.pieces[Piece::King as usize].0 & self.colors[color as usize].0).trailing_zeros();
+        if king_sq as usize / 8 != rank / 8 {
+            return None;
+        }
+        let king_file = king_sq as usize % 8;
+
+        let is_our_rook = |file: usize| self.board[rank + file] == Some((Piece::Rook, color));
+
+        match c {
+            'k' => (king_file + 1..8).rev().find(|&file| is_our_rook(file)),
+            'q' => (0..king_file).find(|&file| is_our_rook(file)),
+            'a'..='h' => {
+                let file = (c as u8 - b'a') as usize;
+                (file != king_file && is_our_rook(file)).then_some(file)
+            }
+            _ => None,
+        }
+        .map(|file| rank + file)
+    }
+}
+
+impl std::fmt::Display for Position {
+    /// Formats the position as its FEN string.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_fen())
+    }
+}
+
+const fn piece_char(piece: Piece, color: Color) -> char {
+    let c = match piece {
+        Piece::Pawn => 'p',
+        Piece::Knight => 'n',
+        Piece::Bishop => 'b',
+        Piece::Rook => 'r',
+        Piece::Queen => 'q',
+        Piece::King => 'k',
+    };
+    match color {
+        Color::White => c.to_ascii_uppercase(),
+        Color::Black => c,
+    }
+}
+
+/// Parses an algebraic square such as `e3`.
+fn parse_square(s: &str) -> Option<u8> {
+    let &[file @ b'a'..=b'h', rank @ b'1'..=b'8'] = s.as_bytes() else {
+        return None;
+    };
+    Some((rank - b'1') * 8 + (file - b'a'))
+}
+
+/// Formats a square index in algebraic notation.
+fn square_name(sq: u8) -> String {
+    format!("{}{}", (b'a' + sq % 8) as char, (b'1' + sq / 8) as char)
 }
@@ -1,4 +1,5 @@
 use super::Position;
+use crate::types::Piece;
 use crate::zobrist::{CASTLE_KEYS, EP_KEYS, PIECE_KEYS, SIDE_KEY};
 
 impl Position {
@@ -20,4 +21,16 @@ impl Position {
         }
         h
     }
+
+    #[inline(always)]
+    /// Recomputes the pawn-structure hash from board state.
+    pub fn compute_pawn_hash(&self) -> u64 {
+        let mut h = 0u64;
+        for (sq, entry) in self.board.iter().enumerate() {
+            if let Some((Piece::Pawn, color)) = *entry {
+                h ^= PIECE_KEYS[color as usize][Piece::Pawn as usize][sq];
+            }
+        }
+        h
+    }
 }
@@ -29,6 +29,7 @@ impl Position {
             en_passant: self.en_passant,
             halfmove: self.halfmove,
             hash: self.hash,
+            pawn_hash: self.pawn_hash,
             captured_piece,
         });
 
@@ -41,19 +42,32 @@ impl Position {
             MoveType::Quiet => {
                 self.hash ^= PIECE_KEYS[color as usize][piece as usize][from];
                 self.hash ^= PIECE_KEYS[color as usize][piece as usize][to];
+                if piece == Piece::Pawn {
+                    self.pawn_hash ^= PIECE_KEYS[color as usize][Piece::Pawn as usize][from];
+                    self.pawn_hash ^= PIECE_KEYS[color as usize][Piece::Pawn as usize][to];
+                }
                 self.move_piece(from, to, color, piece);
             }
             MoveType::Capture => {
                 let (captured_piece, captured_color) = self.piece_at(to).unwrap();
                 self.hash ^= PIECE_KEYS[captured_color as usize][captured_piece as usize][to];
+                if captured_piece == Piece::Pawn {
+                    self.pawn_hash ^= PIECE_KEYS[captured_color as usize][Piece::Pawn as usize][to];
+                }
                 self.remove_piece(to);
                 self.hash ^= PIECE_KEYS[color as usize][piece as usize][from];
                 self.hash ^= PIECE_KEYS[color as usize][piece as usize][to];
+                if piece == Piece::Pawn {
+                    self.pawn_hash ^= PIECE_KEYS[color as usize][Piece::Pawn as usize][from];
+                    self.pawn_hash ^= PIECE_KEYS[color as usize][Piece::Pawn as usize][to];
+                }
                 self.move_piece(from, to, color, piece);
             }
             MoveType::DoublePush => {
                 self.hash ^= PIECE_KEYS[color as usize][piece as usize][from];
                 self.hash ^= PIECE_KEYS[color as usize][piece as usize][to];
+                self.pawn_hash ^= PIECE_KEYS[color as usize][Piece::Pawn as usize][from];
+                self.pawn_hash ^= PIECE_KEYS[color as usize][Piece::Pawn as usize][to];
                 self.move_piece(from, to, color, piece);
                 self.en_passant = Some(((from + to) / 2) as u8);
             }
@@ -67,29 +81,30 @@ impl Position {
                 self.remove_piece(captured);
                 self.hash ^= PIECE_KEYS[color as usize][piece as usize][from];
                 self.hash ^= PIECE_KEYS[color as usize][piece as usize][to];
+                self.pawn_hash ^= PIECE_KEYS[color.flip() as usize][Piece::Pawn as usize][captured];
+                self.pawn_hash ^= PIECE_KEYS[color as usize][Piece::Pawn as usize][from];
+                self.pawn_hash ^= PIECE_KEYS[color as usize][Piece::Pawn as usize][to];
                 self.move_piece(from, to, color, piece);
             }
             MoveType::Castle => {
+                let (rook_from, rook_to) = self.castling_rook_move(to);
                 self.hash ^= PIECE_KEYS[color as usize][piece as usize][from];
                 self.hash ^= PIECE_KEYS[color as usize][piece as usize][to];
-                self.move_piece(from, to, color, piece);
-
-                let (rook_from, rook_to) = match to {
-                    6 => (7, 5),
-                    2 => (0, 3),
-                    62 => (63, 61),
-                    58 => (56, 59),
-                    _ => panic!("Invalid castle"),
-                };
                 self.hash ^= PIECE_KEYS[color as usize][Piece::Rook as usize][rook_from];
                 self.hash ^= PIECE_KEYS[color as usize][Piece::Rook as usize][rook_to];
-                self.move_piece(rook_from, rook_to, color, Piece::Rook);
+
+                // chess960: king and rook squares may overlap, so lift both before placing either.
+                self.remove_piece(from);
+                self.remove_piece(rook_from);
+                self.add_piece(to, color, piece);
+                self.add_piece(rook_to, color, Piece::Rook);
             }
             MoveType::PromotionKnight
             | MoveType::PromotionBishop
             | MoveType::PromotionRook
             | MoveType::PromotionQueen => {
                 self.hash ^= PIECE_KEYS[color as usize][Piece::Pawn as usize][from];
+                self.pawn_hash ^= PIECE_KEYS[color as usize][Piece::Pawn as usize][from];
                 self.remove_piece(from);
                 let promoted = match move_type {
                     MoveType::PromotionKnight => Piece::Knight,
@@ -109,6 +124,7 @@ impl Position {
                 self.hash ^= PIECE_KEYS[captured_color as usize][captured_piece as usize][to];
                 self.remove_piece(to);
                 self.hash ^= PIECE_KEYS[color as usize][Piece::Pawn as usize][from];
+                self.pawn_hash ^= PIECE_KEYS[color as usize][Piece::Pawn as usize][from];
                 self.remove_piece(from);
                 let promoted = match move_type {
                     MoveType::CapturePromotionKnight => Piece::Knight,
@@ -122,11 +138,8 @@ impl Position {
             }
         }
 
-        if piece == Piece::King {
-            self.castling_rights.remove_color(color);
-        }
-        self.castling_rights.remove_rook(from);
-        self.castling_rights.remove_rook(to);
+        self.castling_rights
+            .remove(self.castling_masks[from] | self.castling_masks[to]);
         self.hash ^= CASTLE_KEYS[self.castling_rights.0 as usize];
 
         if piece == Piece::Pawn || m.is_capture() {
@@ -158,6 +171,7 @@ impl Position {
             en_passant: self.en_passant,
             halfmove: self.halfmove,
             hash: self.hash,
+            pawn_hash: self.pawn_hash,
             captured_piece: None,
         });
 
@@ -0,0 +1,231 @@
+//! Standard Algebraic Notation.
+//!
+//! Formatting follows the PGN specification: piece letter, the minimal
+//! disambiguation (file, then rank, then both), `x` for captures, `=Q` for
+//! promotions, `O-O`/`O-O-O` for castling and a `+` or `#` suffix.
+//!
+//! Parsing is tolerant of common variations: zeros in castling, missing or
+//! extra `x`/`-` separators, over-specified origins such as `Ng1f3`, a
+//! promotion piece without `=`, and trailing `+`, `#`, `!`, `?` or `e.p.`.
+//! The result is always resolved against the legal move list, so an
+//! ambiguous or illegal string yields `None`.
+
+use super::Position;
+use crate::types::{Move, MoveCollector, MoveType, Piece};
+
+impl Position {
+    /// Formats a legal move in Standard Algebraic Notation.
+    pub fn move_to_san(&self, mv: Move) -> String {
+        let mut san = String::with_capacity(8);
+
+        if mv.move_type() == MoveType::Castle {
+            san.push_str(if mv.to() % 8 == 6 { "O-O" } else { "O-O-O" });
+        } else {
+            let (piece, _) = self.piece_at(mv.from()).expect("no piece on move origin");
+            let from = mv.from();
+            let to = mv.to();
+            let is_capture = mv.is_capture() || mv.move_type() == MoveType::EnPassant;
+
+            if piece == Piece::Pawn {
+                if is_capture {
+                    san.push(file_char(from));
+                }
+            } else {
+                san.push(piece_letter(piece));
+                self.push_disambiguation(&mut san, mv, piece);
+            }
+
+            if is_capture {
+                san.push('x');
+            }
+            san.push(file_char(to));
+            san.push(rank_char(to));
+
+            if let Some(promoted) = mv.promotion_piece() {
+                san.push('=');
+                san.push(piece_letter(promoted));
+            }
+        }
+
+        let mut after = self.clone();
+        after.make_move(mv);
+        if after.is_in_check() {
+            san.push(if after.is_game_over() { '#' } else { '+' });
+        }
+
+        san
+    }
+
+    /// Formats a line of legal moves played from this position, space separated.
+    pub fn line_to_san(&self, line: &[Move]) -> String {
+        let mut pos = self.clone();
+        let mut sans = Vec::with_capacity(line.len());
+
+        for &mv in line {
+            sans.push(pos.move_to_san(mv));
+            pos.make_move(mv);
+        }
+
+        sans.join(" ")
+    }
+
+    /// Resolves a SAN string to the unique legal move it describes.
+    pub fn parse_san(&self, san: &str) -> Option<Move> {
+        let mut text = san.trim();
+        text = text.strip_suffix("e.p.").unwrap_or(text).trim_end();
+        text = text.trim_end_matches(['+', '#', '!', '?']);
+
+        let mut collector = MoveCollector::new();
+        self.generate_moves(&mut collector);
+        let legal = collector.as_slice();
+
+        // castling: accept both letter O and digit zero forms.
+        let castle = text.replace('0', "O");
+        if castle == "O-O" || castle == "O-O-O" {
+            let kingside = castle == "O-O";
+            return legal
+                .iter()
+                .copied()
+                .find(|mv| mv.move_type() == MoveType::Castle && (mv.to() % 8 == 6) == kingside);
+        }
+
+        let mut chars: Vec<char> = text
+            .chars()
+            .filter(|c| !matches!(c, 'x' | ':' | '-'))
+            .collect();
+
+        let piece = match chars.first() {
+            Some(&c) if "NBRQK".contains(c) => {
+                chars.remove(0);
+                piece_from_letter(c)?
+            }
+            _ => Piece::Pawn,
+        };
+
+        // promotion: `e8=Q`, `e8Q` and `e8q` all name the same move.
+        let mut promotion = None;
+        if piece == Piece::Pawn {
+            if let Some(&last) = chars.last() {
+                if let Some(promoted) = piece_from_letter(last.to_ascii_uppercase()) {
+                    chars.pop();
+                    if chars.last() == Some(&'=') {
+                        chars.pop();
+                    }
+                    promotion = Some(promoted);
+                }
+            }
+        }
+
+        if chars.len() < 2 {
+            return None;
+        }
+        let target = parse_square(chars[chars.len() - 2], chars[chars.len() - 1])?;
+        let origin = &chars[..chars.len() - 2];
+
+        let mut from_file = None;
+        let mut from_rank = None;
+        for &c in origin {
+            match c {
+                'a'..='h' => from_file = Some(c as usize - 'a' as usize),
+                '1'..='8' => from_rank = Some(c as usize - '1' as usize),
+                _ => return None,
+            }
+        }
+
+        let mut candidates = legal.iter().copied().filter(|mv| {
+            mv.move_type() != MoveType::Castle
+                && mv.to() == target
+                && self.piece_at(mv.from()).map(|(p, _)| p) == Some(piece)
+                && from_file.is_none_or(|file| mv.from() % 8 == file)
+                && from_rank.is_none_or(|rank| mv.from() / 8 == rank)
+                && match (mv.promotion_piece(), promotion) {
+                    (Some(promoted), Some(wanted)) => promoted == wanted,
+                    // an unmarked promotion is read as a queen.
+                    (Some(promoted), None) => promoted == Piece::Queen,
+                    (None, wanted) => wanted.is_none(),
+                }
+        });
+
+        let mv = candidates.next()?;
+        candidates.next().is_none().then_some(mv)
+    }
+
+    /// Appends the origin file and/or rank needed to tell `mv` apart from the
+    /// other legal moves of the same piece type to the same square.
+    fn push_disambiguation(&self, san: &mut String, mv: Move, piece: Piece) {
+        let mut collector = MoveCollector::new();
+        self.generate_moves(&mut collector);
+
+        let from = mv.from();
+        let mut ambiguous = false;
+        let mut same_file = false;
+        let mut same_rank = false;
+
+        for other in collector.as_slice() {
+            if other.from() == from
+                || other.to() != mv.to()
+                || other.move_type() == MoveType::Castle
+                || self.piece_at(other.from()).map(|(p, _)| p) != Some(piece)
+            {
+                continue;
+            }
+
+            ambiguous = true;
+            same_file |= other.from() % 8 == from % 8;
+            same_rank |= other.from() / 8 == from / 8;
+        }
+
+        if !ambiguous {
+            return;
+        }
+
+        if !same_file {
+            san.push(file_char(from));
+        } else if !same_rank {
+            san.push(rank_char(from));
+        } else {
+            san.push(file_char(from));
+            san.push(rank_char(from));
+        }
+    }
+}
+
+const fn piece_letter(piece: Piece) -> char {
+    match piece {
+        Piece::Pawn => 'P',
+        Piece::Knight => 'N',
+        Piece::Bishop => 'B',
+        Piece::Rook => 'R',
+        Piece::Queen => 'Q',
+        Piece::King => 'K',
+    }
+}
+
+const fn piece_from_letter(c: char) -> Option<Piece> {
+    match c {
+        'N' => Some(Piece::Knight),
+        'B' => Some(Piece::Bishop),
+        'R' => Some(Piece::Rook),
+        'Q' => Some(Piece::Queen),
+        'K' => Some(Piece::King),
+        _ => None,
+    }
+}
+
+const fn file_char(sq: usize) -> char {
+    (b'a' + (sq % 8) as u8) as char
+}
+
+const fn rank_char(sq: usize) -> char {
+    (b'1' + (sq / 8) as u8) as char
+}
+
+fn parse_square(file: char, rank: char) -> Option<usize> {
+    let file = ('a'..='h')
+        .contains(&file)
+        .then(|| file as usize - 'a' as usize)?;
+    let rank = ('1'..='8')
+        .contains(&rank)
+        .then(|| rank as usize - '1' as usize)?;
+    Some(rank * 8 + file)
+}
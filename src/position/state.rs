@@ -11,6 +11,8 @@ pub struct GameState {
     pub halfmove: u16,
     /// Zobrist hash before the move.
     pub hash: u64,
+    /// Pawn-structure hash before the move.
+    pub pawn_hash: u64,
     /// Captured piece type, used during unmake.
     pub captured_piece: Option<Piece>,
 }
@@ -32,6 +34,12 @@ pub struct Position {
     pub side_to_move: Color,
     /// Current castling rights.
     pub castling_rights: CastleRights,
+    /// Castling rook start squares in `K`, `Q`, `k`, `q` order.
+    pub castling_rooks: [u8; 4],
+    /// Castling rights lost when a move leaves from or lands on each square.
+    pub castling_masks: [u8; 64],
+    /// Whether castling moves use Chess960 king-takes-rook notation.
+    pub chess960: bool,
     /// En passant square, if available.
     pub en_passant: Option<u8>,
     /// Halfmove clock used for the fifty-move rule.
@@ -40,6 +48,8 @@ pub struct Position {
     pub fullmove: u16,
     /// Incrementally maintained Zobrist hash.
     pub hash: u64,
+    /// Incrementally maintained Zobrist hash of the pawns alone.
+    pub pawn_hash: u64,
     /// Reversible state stack used by make/unmake.
     pub history: Vec<GameState>,
 }
@@ -57,6 +67,12 @@ impl Position {
         self.hash
     }
 
+    #[inline(always)]
+    /// Returns the current pawn-structure hash.
+    pub fn pawn_hash(&self) -> u64 {
+        self.pawn_hash
+    }
+
     #[inline(always)]
     /// Returns the current side's pieces of the requested type.
     pub const fn our(&self, piece: Piece) -> Bitboard {
@@ -7,6 +7,7 @@ fn dummy_state(hash: u64) -> GameState {
         en_passant: None,
         halfmove: 1,
         hash,
+        pawn_hash: 0,
         captured_piece: None,
     }
 }
@@ -48,3 +49,340 @@ fn ignores_positions_before_halfmove_window() {
 
     assert!(!pos.is_repetition());
 }
+
+fn legal_moves(pos: &Position) -> Vec<crate::Move> {
+    let mut collector = crate::MoveCollector::new();
+    pos.generate_moves(&mut collector);
+    collector.as_slice().to_vec()
+}
+
+#[test]
+fn parses_shredder_and_x_fen_castling_rights_alike() {
+    let shredder =
+        Position::from_fen("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9")
+            .expect("valid Shredder-FEN");
+    let x_fen =
+        Position::from_fen("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9")
+            .expect("valid X-FEN");
+
+    assert!(shredder.chess960);
+    assert_eq!(shredder.castling_rights, x_fen.castling_rights);
+    assert_eq!(shredder.castling_rooks, x_fen.castling_rooks);
+    assert_eq!(shredder.castling_rook(crate::Color::White, true), 7);
+    assert_eq!(shredder.castling_rook(crate::Color::White, false), 5);
+    assert_eq!(shredder.hash(), x_fen.hash());
+}
+
+#[test]
+fn chess960_castling_with_overlapping_squares_round_trips() {
+    // king on f1 castles queenside while the e1 rook crosses the king's start square.
+    let mut pos = Position::from_fen("k7/8/8/8/8/8/8/4RK1R w EH - 0 1").expect("valid FEN");
+    let original = pos.clone();
+
+    let castles: Vec<_> = legal_moves(&pos)
+        .into_iter()
+        .filter(|mv| mv.move_type() == crate::MoveType::Castle)
+        .collect();
+    assert_eq!(castles.len(), 2);
+
+    for mv in castles {
+        pos.make_move(mv);
+        assert_eq!(pos.hash(), pos.compute_hash());
+        let (king, _) = pos.piece_at(mv.to()).expect("king on target square");
+        assert_eq!(king, crate::Piece::King);
+        assert_eq!(pos.castling_rights, CastleRights::NONE);
+
+        pos.unmake_move(mv);
+        assert_eq!(pos.hash(), original.hash());
+        assert_eq!(pos.occupied().0, original.occupied().0);
+    }
+}
+
+#[test]
+fn chess960_castling_is_written_as_king_takes_rook() {
+    let pos = Position::from_fen("4k3/8/8/8/8/8/8/1R4KR w BH - 0 1").expect("valid FEN");
+    let notation: Vec<_> = legal_moves(&pos)
+        .into_iter()
+        .filter(|mv| mv.move_type() == crate::MoveType::Castle)
+        .map(|mv| pos.move_to_uci(mv))
+        .collect();
+
+    assert!(notation.contains(&"g1h1".to_string()));
+    assert!(notation.contains(&"g1b1".to_string()));
+}
+
+#[test]
+fn chess960_castling_rejects_rook_shielded_king_target() {
+    // the b1 rook blocks the a1 queen today, but would expose c1 once lifted.
+    let pos = Position::from_fen("4k3/8/8/8/8/8/8/qR1K4 w B - 0 1").expect("valid FEN");
+
+    assert!(legal_moves(&pos)
+        .iter()
+        .all(|mv| mv.move_type() != crate::MoveType::Castle));
+}
+
+/// Asserts that `to_fen` reproduces `fen` and that parsing it back restores the same position.
+fn assert_fen_round_trip(pos: &Position, fen: &str) {
+    assert_eq!(pos.to_fen(), fen);
+
+    let parsed = Position::from_fen(fen).expect("to_fen output parses");
+    assert_eq!(parsed.to_string(), fen);
+    assert_eq!(parsed.pieces, pos.pieces, "{fen}");
+    assert_eq!(parsed.colors, pos.colors, "{fen}");
+    assert_eq!(parsed.board, pos.board, "{fen}");
+    assert_eq!(parsed.side_to_move, pos.side_to_move, "{fen}");
+    assert_eq!(parsed.castling_rights, pos.castling_rights, "{fen}");
+    assert_eq!(parsed.en_passant, pos.en_passant, "{fen}");
+    assert_eq!(parsed.halfmove, pos.halfmove, "{fen}");
+    assert_eq!(parsed.fullmove, pos.fullmove, "{fen}");
+    assert_eq!(parsed.hash(), pos.hash(), "{fen}");
+    assert_eq!(parsed.pawn_hash(), pos.pawn_hash(), "{fen}");
+
+    for color in [crate::Color::White, crate::Color::Black] {
+        for kingside in [true, false] {
+            if pos.castling_rights.0 & CastleRights::right(color, kingside) != 0 {
+                assert_eq!(
+                    parsed.castling_rook(color, kingside),
+                    pos.castling_rook(color, kingside),
+                    "{fen}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn to_fen_round_trips_parsed_positions() {
+    for fen in [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        // partial castling rights, black to move, non-trivial clocks.
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b Kq - 3 17",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPPBNnPP/RN1QK2R b KQ - 1 8",
+        // an en passant square with and without a pawn able to take.
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        "rnbqkbnr/pppp1ppp/8/4p3/8/8/PPPPPPPP/RNBQKBNR w KQkq e6 0 2",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        // chess960 rights are written as shredder rook files.
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+    ] {
+        let pos = Position::from_fen(fen).expect("valid FEN");
+        assert_fen_round_trip(&pos, fen);
+    }
+}
+
+#[test]
+fn to_fen_tracks_moves_played_on_the_board() {
+    let mut pos = Position::new();
+    let mut play = |uci: &str| {
+        let mv = legal_moves(&pos)
+            .into_iter()
+            .find(|mv| pos.move_to_uci(*mv) == uci)
+            .expect("legal move");
+        pos.make_move(mv);
+        pos.to_fen()
+    };
+
+    assert_eq!(
+        play("e2e4"),
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+    );
+    assert_eq!(
+        play("g8f6"),
+        "rnbqkb1r/pppppppp/5n2/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 1 2"
+    );
+    assert_eq!(
+        play("e1e2"),
+        "rnbqkb1r/pppppppp/5n2/8/4P3/8/PPPPKPPP/RNBQ1BNR b kq - 2 2"
+    );
+
+    let fen = pos.to_fen();
+    assert_fen_round_trip(&pos, &fen);
+}
+
+#[test]
+fn malformed_fens_report_the_offending_field() {
+    use crate::FenError;
+
+    let cases = [
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR",
+            FenError::MissingField("side to move"),
+        ),
+        ("8/8/8/8/8/8/8 w - - 0 1", FenError::RankCount(7)),
+        (
+            "rnbqkbnr/ppppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            FenError::RankLength {
+                rank: 7,
+                squares: 9,
+            },
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBN w KQkq - 0 1",
+            FenError::RankLength {
+                rank: 1,
+                squares: 7,
+            },
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1",
+            FenError::InvalidPiece {
+                rank: 1,
+                piece: 'X',
+            },
+        ),
+        (
+            "4k3/8/8/8/8/8/8/8 w - - 0 1",
+            FenError::KingCount {
+                color: crate::Color::White,
+                count: 0,
+            },
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 x - - 0 1",
+            FenError::InvalidSideToMove("x".to_string()),
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w K - 0 1",
+            FenError::InvalidCastling('K'),
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - e - 0 1",
+            FenError::InvalidEnPassant("e".to_string()),
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - e4 0 1",
+            FenError::InvalidEnPassant("e4".to_string()),
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - - x 1",
+            FenError::InvalidHalfmove("x".to_string()),
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 0",
+            FenError::InvalidFullmove("0".to_string()),
+        ),
+    ];
+
+    for (fen, expected) in cases {
+        assert_eq!(Position::from_fen(fen).err(), Some(expected), "{fen}");
+    }
+}
+
+#[test]
+fn illegal_positions_fail_validation() {
+    use crate::FenError;
+
+    let cases = [
+        (
+            "P3k3/8/8/8/8/8/8/4K3 w - - 0 1",
+            FenError::PawnOnBackRank(56),
+        ),
+        ("4k3/8/8/8/8/8/8/3KR3 w - - 0 1", FenError::OpponentInCheck),
+        (
+            "4k3/8/8/8/8/8/8/4K3 b - e3 0 1",
+            FenError::ImpossibleEnPassant(20),
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - e6 0 1",
+            FenError::ImpossibleEnPassant(44),
+        ),
+    ];
+
+    for (fen, expected) in cases {
+        assert_eq!(Position::from_fen(fen).err(), Some(expected), "{fen}");
+    }
+
+    let mut pos = Position::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").expect("valid FEN");
+    assert_eq!(pos.validate(), Ok(()));
+    pos.remove_piece(4);
+    assert_eq!(
+        pos.validate(),
+        Err(FenError::KingCount {
+            color: crate::Color::White,
+            count: 0
+        })
+    );
+}
+
+fn san_of(fen: &str, uci: &str) -> String {
+    let pos = Position::from_fen(fen).expect("valid FEN");
+    let mv = legal_moves(&pos)
+        .into_iter()
+        .find(|mv| pos.move_to_uci(*mv) == uci)
+        .expect("legal move");
+    pos.move_to_san(mv)
+}
+
+#[test]
+fn formats_san_with_minimal_disambiguation() {
+    let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    assert_eq!(san_of(start, "e2e4"), "e4");
+    assert_eq!(san_of(start, "g1f3"), "Nf3");
+
+    // knights on b1 and f3 both reach d2: the file tells them apart.
+    let knights = "4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1";
+    assert_eq!(san_of(knights, "b1d2"), "Nbd2");
+    // rooks on a1 and a5 share a file: the rank tells them apart.
+    let rooks = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+    assert_eq!(san_of(rooks, "a1a3"), "R1a3");
+    // queens on h4, e4 and h1 all reach e1: only the full square is unique.
+    let queens = "8/k7/8/8/4Q2Q/8/8/3K3Q w - - 0 1";
+    assert_eq!(san_of(queens, "h4e1"), "Qh4e1");
+}
+
+#[test]
+fn formats_san_captures_promotions_castling_and_checks() {
+    assert_eq!(san_of("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"), "exd6");
+    assert_eq!(san_of("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7b8q"), "b8=Q+");
+    assert_eq!(
+        san_of("r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7a8n"),
+        "bxa8=N"
+    );
+    assert_eq!(san_of("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1", "e1g1"), "O-O");
+    assert_eq!(san_of("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1", "e1c1"), "O-O-O");
+    assert_eq!(san_of("6k1/5ppp/8/8/8/8/8/R3K3 w - - 0 1", "a1a8"), "Ra8#");
+}
+
+#[test]
+fn parses_tolerant_san_variants() {
+    let pos = Position::from_fen("r3k2r/1P6/8/8/8/5N2/8/1N2K2R w Kkq - 0 1").expect("valid FEN");
+    let uci = |san: &str| pos.parse_san(san).map(|mv| pos.move_to_uci(mv));
+
+    assert_eq!(uci("Nbd2"), Some("b1d2".to_string()));
+    assert_eq!(uci("Nb1-d2"), Some("b1d2".to_string()));
+    assert_eq!(uci("Nbd2!?"), Some("b1d2".to_string()));
+    assert_eq!(uci("Nd2"), None, "ambiguous without disambiguation");
+    assert_eq!(uci("O-O"), Some("e1g1".to_string()));
+    assert_eq!(uci("0-0+"), Some("e1g1".to_string()));
+    assert_eq!(uci("O-O-O"), None, "no queenside right");
+    assert_eq!(uci("bxa8=Q+"), Some("b7a8q".to_string()));
+    assert_eq!(uci("bxa8N"), Some("b7a8n".to_string()));
+    assert_eq!(uci("ba8q"), Some("b7a8q".to_string()));
+    assert_eq!(uci("b8"), Some("b7b8q".to_string()));
+    assert_eq!(uci("Ke3"), None, "not a king move");
+    assert_eq!(uci("Qd1"), None, "no queen");
+    assert_eq!(uci("xyz"), None);
+}
+
+#[test]
+fn san_round_trips_every_legal_move_in_tricky_positions() {
+    for fen in [
+        // kiwipete: castling both ways, pins and plenty of captures.
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        // promotions and underpromotions, with and without capture.
+        "r3k3/1P6/8/8/8/8/6p1/4K2R b Kq - 0 1",
+        // three queens and two knights that need file, rank and square disambiguation.
+        "8/k7/8/8/4Q2Q/8/8/3K3Q w - - 0 1",
+        "4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1",
+        // en passant.
+        "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1",
+        // chess960 castling written as king takes rook.
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+    ] {
+        let pos = Position::from_fen(fen).expect("valid FEN");
+        for mv in legal_moves(&pos) {
+            let san = pos.move_to_san(mv);
+            assert_eq!(pos.parse_san(&san), Some(mv), "{san} in {fen}");
+        }
+    }
+}
@@ -25,6 +25,7 @@ impl Position {
         self.en_passant = state.en_passant;
         self.halfmove = state.halfmove;
         self.hash = state.hash;
+        self.pawn_hash = state.pawn_hash;
 
         let to = m.to();
         let from = m.from();
@@ -65,17 +66,11 @@ impl Position {
                 self.add_piece(capture_sq, color.flip(), Piece::Pawn);
             }
             MoveType::Castle => {
-                let (piece, _) = self.piece_at(to).unwrap();
-                self.move_piece(to, from, color, piece);
-
-                let (rook_from, rook_to) = match to {
-                    6 => (7, 5),
-                    2 => (0, 3),
-                    62 => (63, 61),
-                    58 => (56, 59),
-                    _ => panic!("Invalid castle"),
-                };
-                self.move_piece(rook_to, rook_from, color, Piece::Rook);
+                let (rook_from, rook_to) = self.castling_rook_move(to);
+                self.remove_piece(to);
+                self.remove_piece(rook_to);
+                self.add_piece(from, color, Piece::King);
+                self.add_piece(rook_from, color, Piece::Rook);
             }
             MoveType::PromotionKnight
             | MoveType::PromotionBishop
@@ -0,0 +1,102 @@
+use super::{FenError, Position};
+use crate::types::{CastleRights, Color, Piece};
+
+/// Ranks 1 and 8.
+const BACK_RANKS: u64 = 0xFF00_0000_0000_00FF;
+
+impl Position {
+    /// Checks that the position could arise in a legal game.
+    ///
+    /// Each side needs exactly one king, no pawn may stand on a back rank, the
+    /// side that just moved must not be in check, every castling right needs its
+    /// king and rook in place, and an en passant square must sit directly behind
+    /// a pawn that could have just double-pushed.
+    pub fn validate(&self) -> Result<(), FenError> {
+        self.validate_kings()?;
+
+        let pawns = self.pieces[Piece::Pawn as usize].0 & BACK_RANKS;
+        if pawns != 0 {
+            return Err(FenError::PawnOnBackRank(pawns.trailing_zeros() as u8));
+        }
+
+        let them = self.side_to_move.flip();
+        let their_king = self.pieces[Piece::King as usize].0 & self.colors[them as usize].0;
+        if self.is_square_attacked(their_king.trailing_zeros() as usize, self.side_to_move) {
+            return Err(FenError::OpponentInCheck);
+        }
+
+        self.validate_castling()?;
+
+        if let Some(ep) = self.en_passant {
+            self.validate_en_passant(ep)?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks that each side has exactly one king.
+    pub(super) fn validate_kings(&self) -> Result<(), FenError> {
+        for color in [Color::White, Color::Black] {
+            let kings = self.pieces[Piece::King as usize].0 & self.colors[color as usize].0;
+            if kings.count_ones() != 1 {
+                return Err(FenError::KingCount {
+                    color,
+                    count: kings.count_ones(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_castling(&self) -> Result<(), FenError> {
+        for color in [Color::White, Color::Black] {
+            for kingside in [true, false] {
+                if self.castling_rights.0 & CastleRights::right(color, kingside) == 0 {
+                    continue;
+                }
+
+                let rook_sq = self.castling_rook(color, kingside);
+                let king_sq = (self.pieces[Piece::King as usize].0 & self.colors[color as usize].0)
+                    .trailing_zeros() as usize;
+                let rank = if color == Color::White { 0 } else { 7 };
+
+                if self.board[rook_sq] != Some((Piece::Rook, color))
+                    || king_sq / 8 != rank
+                    || rook_sq / 8 != rank
+                    || (rook_sq > king_sq) != kingside
+                {
+                    let c = match (kingside, color) {
+                        (true, Color::White) => 'K',
+                        (false, Color::White) => 'Q',
+                        (true, Color::Black) => 'k',
+                        (false, Color::Black) => 'q',
+                    };
+                    return Err(FenError::InvalidCastling(c));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_en_passant(&self, ep: u8) -> Result<(), FenError> {
+        let ep_sq = ep as usize;
+        // the pawn that double-pushed moved from `from` through `ep_sq` to `pawn`.
+        let (from, pawn, expected_rank) = match self.side_to_move {
+            Color::White => (ep_sq + 8, ep_sq.wrapping_sub(8), 5),
+            Color::Black => (ep_sq.wrapping_sub(8), ep_sq + 8, 2),
+        };
+
+        let mover = self.side_to_move.flip();
+        if ep_sq / 8 != expected_rank
+            || self.board[ep_sq].is_some()
+            || self.board[from].is_some()
+            || self.board[pawn] != Some((Piece::Pawn, mover))
+        {
+            return Err(FenError::ImpossibleEnPassant(ep));
+        }
+
+        Ok(())
+    }
+}
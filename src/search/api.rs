@@ -1,15 +1,26 @@
-use super::limits::SearchLimits;
+use super::limits::{PonderSignal, SearchLimits};
+use super::options::SearchOptions;
 use super::root::run_search;
+use super::score::Score;
 use crate::{eval::EvalProvider, tpt::TranspositionTable, Move, Position};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
+/// One ranked root line from a completed search.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PvLine {
+    /// Score of the line from the root side's perspective.
+    pub score: Score,
+    /// Moves of the line, starting with the root move.
+    pub pv: Vec<Move>,
+}
+
 /// Result returned by a completed search.
 pub struct SearchInfo {
     /// Best move found at the completed search depth.
     pub best_move: Move,
-    /// Score in centipawns or mate-score space.
-    pub score: i32,
+    /// Score of the best line, in centipawns or moves to mate.
+    pub score: Score,
     /// Deepest fully completed root depth.
     pub depth: u8,
     /// Total nodes visited.
@@ -18,6 +29,10 @@ pub struct SearchInfo {
     pub time_ms: u64,
     /// Number of transposition table hits.
     pub tt_hits: u64,
+    /// Principal variation starting with `best_move`.
+    pub pv: Vec<Move>,
+    /// Best root lines ranked by score; holds `MultiPV` entries, the first being `pv`.
+    pub lines: Vec<PvLine>,
 }
 
 /// Runs an iterative-deepening search from `pos` and returns the best completed result.
@@ -25,7 +40,7 @@ pub fn search(
     pos: &Position,
     max_depth: u8,
     max_time_ms: Option<u64>,
-    tt: &mut TranspositionTable,
+    tt: &TranspositionTable,
 ) -> Option<SearchInfo> {
     search_with_eval(
         pos,
@@ -41,21 +56,50 @@ pub fn search_with_eval<E: EvalProvider>(
     pos: &Position,
     max_depth: u8,
     limits: SearchLimits,
-    tt: &mut TranspositionTable,
+    tt: &TranspositionTable,
+    eval: E,
+) -> Option<SearchInfo> {
+    search_with_options(pos, max_depth, limits, &SearchOptions::new(), tt, eval)
+}
+
+/// Runs an iterative-deepening search with explicit search options such as `MultiPV`.
+pub fn search_with_options<E: EvalProvider>(
+    pos: &Position,
+    max_depth: u8,
+    limits: SearchLimits,
+    options: &SearchOptions,
+    tt: &TranspositionTable,
     eval: E,
 ) -> Option<SearchInfo> {
     let stop_signal = Arc::new(AtomicBool::new(false));
-    search_with_stop_signal(pos, max_depth, limits, tt, stop_signal, eval)
+    search_with_stop_signal(pos, max_depth, limits, options, tt, stop_signal, None, eval)
 }
 
+/// Runs a search that can be stopped externally and, when `ponder` is set, keeps
+/// searching without a clock until the ponder signal is released.
+#[expect(
+    clippy::too_many_arguments,
+    reason = "the UCI front-end passes its shared signals straight through"
+)]
 pub(crate) fn search_with_stop_signal<E: EvalProvider>(
     pos: &Position,
     max_depth: u8,
     limits: SearchLimits,
-    tt: &mut TranspositionTable,
+    options: &SearchOptions,
+    tt: &TranspositionTable,
     stop_signal: Arc<AtomicBool>,
+    ponder: Option<Arc<PonderSignal>>,
     eval: E,
 ) -> Option<SearchInfo> {
     tt.new_search();
-    run_search(pos, max_depth, limits, tt, stop_signal, &eval)
+    run_search(
+        pos,
+        max_depth,
+        limits,
+        options,
+        tt,
+        stop_signal,
+        ponder,
+        &eval,
+    )
 }
@@ -1,11 +1,62 @@
+use super::correction::CorrectionHistory;
+use super::limits::PonderSignal;
 use super::ordering::MoveHistory;
+use super::pv::PvTable;
+use super::stack::SearchStack;
 use crate::{eval::EvalProvider, tpt::TranspositionTable, Position};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 
 const NODE_TIME_CHECK_MASK: u64 = 63;
 
+/// Per-thread counter slot, padded to its own cache line so helpers never contend.
+#[repr(align(64))]
+#[derive(Default)]
+struct CounterSlot {
+    nodes: AtomicU64,
+    tt_hits: AtomicU64,
+}
+
+/// Node and TT-hit counters published by every search thread.
+///
+/// Each thread owns one slot and overwrites it with its local totals, so the
+/// main thread can report aggregate numbers without any shared increments.
+pub(crate) struct ThreadCounters {
+    slots: Box<[CounterSlot]>,
+}
+
+impl ThreadCounters {
+    pub(crate) fn new(threads: usize) -> Self {
+        Self {
+            slots: (0..threads.max(1))
+                .map(|_| CounterSlot::default())
+                .collect(),
+        }
+    }
+
+    #[inline(always)]
+    fn publish(&self, thread_id: usize, nodes: u64, tt_hits: u64) {
+        let slot = &self.slots[thread_id];
+        slot.nodes.store(nodes, Ordering::Relaxed);
+        slot.tt_hits.store(tt_hits, Ordering::Relaxed);
+    }
+
+    pub(crate) fn total_nodes(&self) -> u64 {
+        self.slots
+            .iter()
+            .map(|slot| slot.nodes.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    pub(crate) fn total_tt_hits(&self) -> u64 {
+        self.slots
+            .iter()
+            .map(|slot| slot.tt_hits.load(Ordering::Relaxed))
+            .sum()
+    }
+}
+
 /// Mutable counters and stop state carried through a single search.
 pub(crate) struct SearchStats {
     pub(crate) nodes: u64,
@@ -13,6 +64,10 @@ pub(crate) struct SearchStats {
     stop_signal: Option<Arc<AtomicBool>>,
     start_time: Instant,
     hard_time_ms: Option<u64>,
+    node_limit: Option<u64>,
+    ponder: Option<Arc<PonderSignal>>,
+    counters: Arc<ThreadCounters>,
+    thread_id: usize,
 }
 
 impl SearchStats {
@@ -20,6 +75,10 @@ impl SearchStats {
         stop_signal: Option<Arc<AtomicBool>>,
         start_time: Instant,
         hard_time_ms: Option<u64>,
+        node_limit: Option<u64>,
+        ponder: Option<Arc<PonderSignal>>,
+        counters: Arc<ThreadCounters>,
+        thread_id: usize,
     ) -> Self {
         Self {
             nodes: 0,
@@ -27,17 +86,34 @@ impl SearchStats {
             stop_signal,
             start_time,
             hard_time_ms,
+            node_limit,
+            ponder,
+            counters,
+            thread_id,
         }
     }
 
     #[inline(always)]
     pub(crate) fn should_stop(&self) -> bool {
+        // node budget: this thread alone may exhaust it, which keeps single-threaded runs exact.
+        if self.node_limit.is_some_and(|limit| self.nodes >= limit) && !self.is_pondering() {
+            self.signal_stop();
+            return true;
+        }
+
         if self.nodes & NODE_TIME_CHECK_MASK == 0 {
-            if let Some(max_time) = self.hard_time_ms {
-                if self.start_time.elapsed().as_millis() as u64 >= max_time {
-                    if let Some(signal) = &self.stop_signal {
-                        signal.store(true, Ordering::Relaxed);
-                    }
+            self.publish();
+
+            if let (Some(max_time), Some(clock_start)) = (self.hard_time_ms, self.clock_start()) {
+                if clock_start.elapsed().as_millis() as u64 >= max_time {
+                    self.signal_stop();
+                    return true;
+                }
+            }
+
+            if let Some(limit) = self.node_limit {
+                if self.counters.total_nodes() >= limit && !self.is_pondering() {
+                    self.signal_stop();
                     return true;
                 }
             }
@@ -52,6 +128,63 @@ impl SearchStats {
         false
     }
 
+    /// Counts a newly entered node, or returns `false` without counting it once the
+    /// search has to stop, so nodes visited while unwinding never overshoot a node limit.
+    #[inline(always)]
+    pub(crate) fn enter_node(&mut self) -> bool {
+        if self.should_stop() {
+            return false;
+        }
+
+        self.nodes += 1;
+        true
+    }
+
+    /// Publishes this thread's counters to the shared per-thread slots.
+    #[inline(always)]
+    pub(crate) fn publish(&self) {
+        self.counters
+            .publish(self.thread_id, self.nodes, self.tt_hits);
+    }
+
+    /// Returns the node count summed over every search thread.
+    #[inline(always)]
+    pub(crate) fn total_nodes(&self) -> u64 {
+        self.publish();
+        self.counters.total_nodes()
+    }
+
+    /// Raises the shared stop signal so every search thread winds down.
+    #[inline(always)]
+    pub(crate) fn signal_stop(&self) {
+        if let Some(signal) = &self.stop_signal {
+            signal.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns `true` for the thread that owns reporting and time management.
+    #[inline(always)]
+    pub(crate) const fn is_main_thread(&self) -> bool {
+        self.thread_id == 0
+    }
+
+    /// Instant time limits are measured from: the search start, or the
+    /// `ponderhit` when pondering. `None` while still pondering.
+    #[inline(always)]
+    pub(crate) fn clock_start(&self) -> Option<Instant> {
+        match &self.ponder {
+            Some(ponder) => ponder.clock_start(),
+            None => Some(self.start_time),
+        }
+    }
+
+    #[inline(always)]
+    fn is_pondering(&self) -> bool {
+        self.ponder
+            .as_ref()
+            .is_some_and(|ponder| ponder.is_pondering())
+    }
+
     #[inline(always)]
     pub(crate) fn elapsed_ms(&self) -> u64 {
         self.start_time.elapsed().as_millis() as u64
@@ -59,10 +192,13 @@ impl SearchStats {
 }
 
 pub(crate) struct SearchContext<'a, E: EvalProvider> {
-    pub(crate) tt: &'a mut TranspositionTable,
+    pub(crate) tt: &'a TranspositionTable,
     pub(crate) eval: &'a E,
     pub(crate) eval_state: Box<E::State>,
     pub(crate) history: MoveHistory,
+    pub(crate) correction: CorrectionHistory,
+    pub(crate) pv: Box<PvTable>,
+    pub(crate) stack: SearchStack,
     pub(crate) stats: SearchStats,
 }
 
@@ -70,17 +206,18 @@ impl<'a, E: EvalProvider> SearchContext<'a, E> {
     pub(crate) fn new(
         pos: &Position,
         eval: &'a E,
-        tt: &'a mut TranspositionTable,
-        stop_signal: Arc<AtomicBool>,
-        hard_time_ms: Option<u64>,
-        start_time: Instant,
+        tt: &'a TranspositionTable,
+        stats: SearchStats,
     ) -> Self {
         Self {
             tt,
             eval,
             eval_state: Box::new(eval.new_state(pos)),
             history: MoveHistory::new(),
-            stats: SearchStats::new(Some(stop_signal), start_time, hard_time_ms),
+            correction: CorrectionHistory::new(),
+            pv: Box::new(PvTable::new()),
+            stack: SearchStack::new(),
+            stats,
         }
     }
 }
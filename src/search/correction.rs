@@ -0,0 +1,109 @@
+use super::features;
+use super::score::Score;
+use crate::Position;
+
+/// Entries per side to move; the pawn hash is reduced modulo this.
+const CORRECTION_SIZE: usize = 16_384;
+
+/// Fixed-point units per centipawn in a stored correction.
+const CORRECTION_GRAIN: i32 = 256;
+
+/// Largest correction an entry can hold, in centipawns.
+const MAX_CORRECTION: i32 = 128;
+
+/// Updates are blended in at `min(depth + 1, MAX_CORRECTION_WEIGHT) / CORRECTION_WEIGHT_SCALE`.
+const MAX_CORRECTION_WEIGHT: i32 = 16;
+const CORRECTION_WEIGHT_SCALE: i32 = 256;
+
+/// Learned offset between search results and static eval, keyed by side to move
+/// and pawn structure.
+///
+/// Positions sharing a pawn structure tend to be misjudged by the evaluator in the
+/// same direction, so each entry keeps a running average of how far searches
+/// landed from the raw eval and shifts later evals of that structure toward it.
+pub(crate) struct CorrectionHistory {
+    table: Box<[[i32; CORRECTION_SIZE]]>,
+}
+
+impl CorrectionHistory {
+    pub(crate) fn new() -> Self {
+        Self {
+            table: vec![[0; CORRECTION_SIZE]; 2].into_boxed_slice(),
+        }
+    }
+
+    #[inline(always)]
+    fn entry(&self, pos: &Position) -> &i32 {
+        &self.table[pos.side_to_move as usize][pos.pawn_hash() as usize % CORRECTION_SIZE]
+    }
+
+    #[inline(always)]
+    fn entry_mut(&mut self, pos: &Position) -> &mut i32 {
+        &mut self.table[pos.side_to_move as usize][pos.pawn_hash() as usize % CORRECTION_SIZE]
+    }
+
+    /// Returns `raw_eval` shifted by the correction learned for this pawn structure.
+    #[inline(always)]
+    pub(crate) fn correct(&self, pos: &Position, raw_eval: i32) -> i32 {
+        if !features::CORRECTION_HISTORY {
+            return raw_eval;
+        }
+
+        raw_eval + self.entry(pos) / CORRECTION_GRAIN
+    }
+
+    /// Moves the correction for `pos` toward `score - raw_eval`, trusting deeper
+    /// searches more. Mate scores say nothing about the eval and are ignored.
+    #[inline(always)]
+    pub(crate) fn update(&mut self, pos: &Position, depth: u8, score: i32, raw_eval: i32) {
+        if !features::CORRECTION_HISTORY || Score::from_internal(score).is_mate() {
+            return;
+        }
+
+        let target = (score - raw_eval).clamp(-MAX_CORRECTION, MAX_CORRECTION) * CORRECTION_GRAIN;
+        let weight = (depth as i32 + 1).min(MAX_CORRECTION_WEIGHT);
+        let entry = self.entry_mut(pos);
+        *entry = (*entry * (CORRECTION_WEIGHT_SCALE - weight) + target * weight)
+            / CORRECTION_WEIGHT_SCALE;
+    }
+}
+
+impl Default for CorrectionHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CorrectionHistory, MAX_CORRECTION};
+    use crate::search::{features, params::MATE_VALUE};
+    use crate::Position;
+
+    #[test]
+    fn corrections_follow_search_results_and_are_shared_by_pawn_structure() {
+        if !features::CORRECTION_HISTORY {
+            return;
+        }
+
+        // same pawns, different pieces: both positions read the same entry.
+        let pos = Position::from_fen("4k3/pp3ppp/8/8/8/8/PP3PPP/4K3 w - - 0 1").expect("valid FEN");
+        let twin =
+            Position::from_fen("3qk3/pp3ppp/8/8/8/8/PP3PPP/2N1K3 w - - 0 1").expect("valid FEN");
+        let mut correction = CorrectionHistory::new();
+
+        for _ in 0..200 {
+            correction.update(&pos, 12, 60, 20);
+        }
+        let learned = correction.correct(&twin, 0);
+        assert!((30..=40).contains(&learned), "{learned}");
+
+        correction.update(&pos, 12, MATE_VALUE - 10, 20);
+        assert_eq!(correction.correct(&twin, 0), learned);
+
+        for _ in 0..400 {
+            correction.update(&pos, 20, -5_000, 0);
+        }
+        assert!(correction.correct(&pos, 0) >= -MAX_CORRECTION);
+    }
+}
@@ -6,13 +6,18 @@ pub(crate) const CHECK_EXTENSIONS: bool = cfg!(feature = "check-extensions");
 
 pub(crate) const NULL_MOVE: bool = cfg!(feature = "null-move");
 pub(crate) const LMR: bool = cfg!(feature = "lmr");
+pub(crate) const LMP: bool = cfg!(feature = "lmp");
 pub(crate) const FUTILITY: bool = cfg!(feature = "futility");
 pub(crate) const REVERSE_FUTILITY: bool = cfg!(feature = "reverse-futility");
 pub(crate) const RAZORING: bool = cfg!(feature = "razoring");
 pub(crate) const PROBCUT: bool = cfg!(feature = "probcut");
+pub(crate) const CORRECTION_HISTORY: bool = cfg!(feature = "correction-history");
 
 pub(crate) const TT_CUTOFFS: bool = cfg!(feature = "tt-cutoffs");
 pub(crate) const TT_MOVE_ORDERING: bool = cfg!(feature = "tt-move-ordering");
 pub(crate) const KILLER_MOVES: bool = cfg!(feature = "killer-moves");
 pub(crate) const HISTORY_HEURISTIC: bool = cfg!(feature = "history-heuristic");
+pub(crate) const CONTINUATION_HISTORY: bool = cfg!(feature = "continuation-history");
+pub(crate) const CAPTURE_HISTORY: bool = cfg!(feature = "capture-history");
+pub(crate) const HISTORY_GRAVITY: bool = cfg!(feature = "history-gravity");
 pub(crate) const SEE: bool = cfg!(feature = "see");
@@ -1,5 +1,6 @@
 mod futility;
 mod iid;
+mod lmp;
 mod lmr;
 mod null_move;
 mod probcut;
@@ -10,6 +11,7 @@ pub use futility::{
     should_prune_futility, should_rfp_prune,
 };
 pub use iid::try_iid;
+pub use lmp::{can_use_late_move_pruning, get_lmp_threshold, should_prune_late_move};
 pub use lmr::{calculate_lmr_reduction, init_lmr, should_reduce_lmr};
 pub use null_move::try_null_move_pruning;
 pub use probcut::try_probcut;
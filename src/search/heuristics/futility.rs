@@ -31,7 +31,9 @@ pub fn can_use_reverse_futility(depth: u8, in_check: bool, pv_node: bool, beta:
 }
 
 #[inline(always)]
-pub fn get_rfp_margin(depth: u8) -> i32 {
+pub fn get_rfp_margin(depth: u8, improving: bool) -> i32 {
+    // improving: an eval that is still rising can be trusted with the margin of a shallower node.
+    let depth = depth.saturating_sub(improving as u8);
     if depth as usize >= RFP_MARGINS.len() {
         return RFP_MARGINS[RFP_MARGINS.len() - 1];
     }
@@ -80,7 +82,9 @@ pub fn can_use_futility_pruning(
 }
 
 #[inline(always)]
-pub fn get_futility_margin(depth: u8) -> i32 {
+pub fn get_futility_margin(depth: u8, improving: bool) -> i32 {
+    // improving: give quiets the margin of a deeper node before writing them off.
+    let depth = depth.saturating_add(improving as u8);
     if depth as usize >= FUTILITY_MARGINS.len() {
         return FUTILITY_MARGINS[FUTILITY_MARGINS.len() - 1];
     }
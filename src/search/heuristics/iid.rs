@@ -21,21 +21,19 @@ pub fn try_iid<E: EvalProvider>(
     depth: u8,
     alpha: i32,
     beta: i32,
-    pv_node: bool,
-    has_tt_move: bool,
+    node: NodeState,
     in_check: bool,
-    ply: usize,
 ) -> Option<Move> {
     // iid: run a cheaper preliminary search to manufacture a good tt move for ordering.
-    if !features::IID || has_tt_move || depth < IID_MIN_DEPTH || in_check {
+    if !features::IID || depth < IID_MIN_DEPTH || in_check {
         return None;
     }
 
-    if !pv_node && depth < IID_MIN_DEPTH + 2 {
+    if !node.pv_node && depth < IID_MIN_DEPTH + 2 {
         return None;
     }
 
-    let reduction = iid_reduction(depth, pv_node);
+    let reduction = iid_reduction(depth, node.pv_node);
     let iid_depth = depth.saturating_sub(reduction);
 
     search_node(
@@ -44,7 +42,7 @@ pub fn try_iid<E: EvalProvider>(
         iid_depth,
         alpha,
         beta,
-        NodeState::new(true, pv_node, None, ply),
+        NodeState::new(true, node.pv_node, None, node.ply),
     );
 
     ctx.tt.probe(pos.hash()).map(|entry| entry.best_move)
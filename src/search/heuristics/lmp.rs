@@ -0,0 +1,72 @@
+use crate::search::features;
+use crate::Move;
+
+const MAX_LMP_DEPTH: u8 = 8;
+const LMP_BASE_MOVES: usize = 3;
+
+#[inline(always)]
+pub fn can_use_late_move_pruning(
+    depth: u8,
+    in_check: bool,
+    pv_node: bool,
+    alpha: i32,
+    beta: i32,
+) -> bool {
+    // late move pruning: only cut the move list short in shallow, quiet non-pv positions.
+    if !features::LMP {
+        return false;
+    }
+
+    if in_check || pv_node || depth == 0 || depth > MAX_LMP_DEPTH {
+        return false;
+    }
+
+    const MATE_BOUND: i32 = 40_000;
+    alpha.abs() <= MATE_BOUND && beta.abs() <= MATE_BOUND
+}
+
+#[inline(always)]
+pub fn get_lmp_threshold(depth: u8, improving: bool) -> usize {
+    // improving: a rising eval keeps twice as many quiets in play.
+    let depth = depth as usize;
+    let threshold = LMP_BASE_MOVES + depth * depth;
+
+    if improving {
+        threshold
+    } else {
+        threshold / 2
+    }
+}
+
+#[inline(always)]
+pub fn should_prune_late_move(mv: Move, move_num: usize, threshold: usize) -> bool {
+    // late move pruning test: skip quiets once enough earlier moves have failed to raise alpha.
+    if mv.is_capture() || mv.is_promotion() {
+        return false;
+    }
+
+    move_num >= threshold
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_lmp_threshold, should_prune_late_move};
+    use crate::{Move, MoveType};
+
+    #[test]
+    fn improving_nodes_keep_more_quiets_before_pruning() {
+        let quiet = Move::new(12, 20, MoveType::Quiet);
+        let capture = Move::new(12, 21, MoveType::Capture);
+
+        let improving = get_lmp_threshold(3, true);
+        let worsening = get_lmp_threshold(3, false);
+        assert_eq!(improving, 12);
+        assert_eq!(worsening, 6);
+
+        // between the two thresholds only the worsening node gives up on quiets.
+        assert!(should_prune_late_move(quiet, worsening, worsening));
+        assert!(!should_prune_late_move(quiet, worsening, improving));
+        assert!(should_prune_late_move(quiet, improving, improving));
+        assert!(!should_prune_late_move(capture, improving, improving));
+    }
+}
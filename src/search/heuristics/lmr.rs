@@ -13,6 +13,7 @@ const QUIET_DIVISOR: f32 = 2.0;
 const CAPTURE_BASE: f32 = 0.10;
 const CAPTURE_DIVISOR: f32 = 2.85;
 const PV_REDUCTION: u8 = 1;
+const NOT_IMPROVING_REDUCTION: u8 = 1;
 
 static LMR_TABLE: OnceLock<[[u8; MAX_MOVES]; MAX_DEPTH]> = OnceLock::new();
 
@@ -68,7 +69,13 @@ pub fn should_reduce_lmr(
 }
 
 #[inline(always)]
-pub fn calculate_lmr_reduction(depth: u8, move_num: usize, pv_node: bool, mv: Move) -> u8 {
+pub fn calculate_lmr_reduction(
+    depth: u8,
+    move_num: usize,
+    pv_node: bool,
+    improving: bool,
+    mv: Move,
+) -> u8 {
     // lmr reduction: compute how many plies to shave off before the verification re-search.
     if !features::LMR || depth < LMR_MIN_DEPTH || move_num < LMR_FULL_DEPTH_MOVES {
         return 0;
@@ -87,6 +94,11 @@ pub fn calculate_lmr_reduction(depth: u8, move_num: usize, pv_node: bool, mv: Mo
         reduction = capture_reduction.min(max_reduction).max(0.0) as u8;
     }
 
+    // improving: late moves of a node whose eval is falling are less likely to matter.
+    if !improving {
+        reduction += NOT_IMPROVING_REDUCTION;
+    }
+
     if pv_node && reduction > PV_REDUCTION {
         reduction = reduction.saturating_sub(PV_REDUCTION);
     }
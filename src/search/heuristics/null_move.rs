@@ -10,13 +10,12 @@ pub fn try_null_move_pruning<E: EvalProvider>(
     ctx: &mut SearchContext<'_, E>,
     depth: u8,
     beta: i32,
-    allow_null: bool,
+    node: NodeState,
     in_check: bool,
     static_eval: i32,
-    ply: usize,
 ) -> Option<i32> {
     // null move pruning: if passing still holds beta, this node is probably already a cutoff.
-    if !features::NULL_MOVE || !allow_null || in_check || depth < 3 || static_eval < beta {
+    if !features::NULL_MOVE || !node.allow_null || in_check || depth < 3 || static_eval < beta {
         return None;
     }
 
@@ -30,6 +29,7 @@ pub fn try_null_move_pruning<E: EvalProvider>(
         return None;
     }
 
+    ctx.stack.set_move(node.ply, None, 0);
     ctx.eval.update_on_null_move(&mut ctx.eval_state, pos);
     pos.make_null_move();
 
@@ -45,7 +45,7 @@ pub fn try_null_move_pruning<E: EvalProvider>(
         null_depth,
         -beta,
         -beta + 1,
-        NodeState::new(false, false, None, ply + 1),
+        NodeState::new(false, false, None, node.ply + 1),
     );
 
     pos.unmake_null_move();
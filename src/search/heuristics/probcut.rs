@@ -2,6 +2,7 @@ use crate::eval::EvalProvider;
 use crate::search::context::SearchContext;
 use crate::search::features;
 use crate::search::node::{search_node, NodeState};
+use crate::search::stack::PieceTo;
 use crate::Position;
 
 const PROBCUT_MARGIN: i32 = 150;
@@ -34,6 +35,7 @@ pub fn try_probcut<E: EvalProvider>(
     let moves = collector.as_slice();
 
     for &mv in moves {
+        ctx.stack.set_move(ply, Some(PieceTo::new(pos, mv)), 0);
         let delta = ctx.eval.update_on_move(&mut ctx.eval_state, pos, mv);
         pos.make_move(mv);
 
@@ -1,6 +1,7 @@
 use crate::eval::EvalProvider;
 use crate::search::context::SearchContext;
 use crate::search::features;
+use crate::search::node::NodeState;
 use crate::search::qsearch::qsearch;
 use crate::Position;
 
@@ -18,12 +19,12 @@ pub fn try_razoring<E: EvalProvider>(
     ctx: &mut SearchContext<'_, E>,
     depth: u8,
     alpha: i32,
+    node: NodeState,
     in_check: bool,
-    pv_node: bool,
     static_eval: i32,
 ) -> Option<i32> {
     // razoring: drop to qsearch early when a shallow node already looks hopeless versus alpha.
-    if !features::RAZORING || depth == 0 || depth > 3 || in_check || pv_node {
+    if !features::RAZORING || depth == 0 || depth > 3 || in_check || node.pv_node {
         return None;
     }
 
@@ -35,7 +36,7 @@ pub fn try_razoring<E: EvalProvider>(
     let margin = RAZOR_MARGINS[depth as usize];
 
     if static_eval + margin < alpha {
-        let razor_score = qsearch(pos, ctx, alpha - margin, alpha - margin + 1, 0);
+        let razor_score = qsearch(pos, ctx, alpha - margin, alpha - margin + 1, node.ply);
 
         if razor_score < alpha - margin {
             return Some(razor_score);
@@ -1,7 +1,8 @@
+use std::sync::{Condvar, Mutex, OnceLock};
 use std::time::Instant;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum SearchLimits {
+enum TimeLimit {
     Infinite,
     MoveTime {
         hard_time_ms: u64,
@@ -12,36 +13,130 @@ pub enum SearchLimits {
     },
 }
 
+/// Stopping conditions for one search: a time budget plus optional node and mate goals.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SearchLimits {
+    time: TimeLimit,
+    nodes: Option<u64>,
+    mate: Option<u32>,
+}
+
 impl SearchLimits {
+    const fn with_time(time: TimeLimit) -> Self {
+        Self {
+            time,
+            nodes: None,
+            mate: None,
+        }
+    }
+
     pub const fn infinite() -> Self {
-        Self::Infinite
+        Self::with_time(TimeLimit::Infinite)
     }
 
     pub const fn movetime(hard_time_ms: u64) -> Self {
-        Self::MoveTime { hard_time_ms }
+        Self::with_time(TimeLimit::MoveTime { hard_time_ms })
     }
 
     pub const fn clock(soft_time_ms: u64, hard_time_ms: u64) -> Self {
-        Self::Clock {
+        Self::with_time(TimeLimit::Clock {
             soft_time_ms,
             hard_time_ms,
-        }
+        })
     }
 
     pub const fn from_max_time(max_time_ms: Option<u64>) -> Self {
         match max_time_ms {
-            Some(hard_time_ms) => Self::MoveTime { hard_time_ms },
-            None => Self::Infinite,
+            Some(hard_time_ms) => Self::movetime(hard_time_ms),
+            None => Self::infinite(),
         }
     }
 
+    /// Stops the search once this many nodes have been searched across all threads.
+    pub const fn with_nodes(mut self, nodes: u64) -> Self {
+        self.nodes = Some(nodes);
+        self
+    }
+
+    /// Stops the search once a forced mate in at most `moves` moves is proven.
+    pub const fn with_mate(mut self, moves: u32) -> Self {
+        self.mate = Some(moves);
+        self
+    }
+
     pub const fn hard_time_ms(self) -> Option<u64> {
-        match self {
-            Self::Infinite => None,
-            Self::MoveTime { hard_time_ms } => Some(hard_time_ms),
-            Self::Clock { hard_time_ms, .. } => Some(hard_time_ms),
+        match self.time {
+            TimeLimit::Infinite => None,
+            TimeLimit::MoveTime { hard_time_ms } => Some(hard_time_ms),
+            TimeLimit::Clock { hard_time_ms, .. } => Some(hard_time_ms),
+        }
+    }
+
+    pub const fn node_limit(self) -> Option<u64> {
+        self.nodes
+    }
+
+    pub const fn mate_limit(self) -> Option<u32> {
+        self.mate
+    }
+}
+
+/// Ponder state shared between the UCI loop and a search started with `go ponder`.
+///
+/// While pondering no time limit applies. Once released by `ponderhit` (or `stop`),
+/// the search limits are measured from the moment of release.
+#[derive(Debug, Default)]
+pub(crate) struct PonderSignal {
+    released_at: OnceLock<Instant>,
+    // only guards the wakeup; the release itself lives in `released_at` so the search
+    // can check it without locking.
+    release_lock: Mutex<()>,
+    released: Condvar,
+}
+
+impl PonderSignal {
+    pub(crate) const fn new() -> Self {
+        Self {
+            released_at: OnceLock::new(),
+            release_lock: Mutex::new(()),
+            released: Condvar::new(),
         }
     }
+
+    /// Returns `true` until the opponent's move is confirmed or the search is stopped.
+    #[inline(always)]
+    pub(crate) fn is_pondering(&self) -> bool {
+        self.released_at.get().is_none()
+    }
+
+    /// Ends pondering and starts the clock; later calls keep the first release time.
+    pub(crate) fn ponderhit(&self) {
+        let _ = self.released_at.set(Instant::now());
+
+        let _guard = self
+            .release_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.released.notify_all();
+    }
+
+    /// Blocks the calling thread until `ponderhit` or `stop` releases the signal.
+    pub(crate) fn wait_for_release(&self) {
+        let guard = self
+            .release_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let _guard = self
+            .released
+            .wait_while(guard, |_| self.is_pondering())
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+    }
+
+    /// Instant the clock started, or `None` while still pondering.
+    #[inline(always)]
+    pub(crate) fn clock_start(&self) -> Option<Instant> {
+        self.released_at.get().copied()
+    }
 }
 
 pub(crate) fn should_stop_next_iteration(
@@ -51,13 +146,13 @@ pub(crate) fn should_stop_next_iteration(
 ) -> bool {
     let elapsed_total = start_time.elapsed().as_millis() as u64;
 
-    match limits {
-        SearchLimits::Infinite => false,
-        SearchLimits::MoveTime { hard_time_ms } => {
+    match limits.time {
+        TimeLimit::Infinite => false,
+        TimeLimit::MoveTime { hard_time_ms } => {
             let time_remaining = hard_time_ms.saturating_sub(elapsed_total);
             time_remaining == 0 || current_depth_time >= time_remaining
         }
-        SearchLimits::Clock {
+        TimeLimit::Clock {
             soft_time_ms,
             hard_time_ms,
         } => {
@@ -2,27 +2,34 @@
 
 mod api;
 mod context;
+mod correction;
 mod features;
 mod heuristics;
 mod limits;
 mod node;
+mod options;
 mod ordering;
 mod output;
 mod params;
+mod pv;
 pub(crate) mod qsearch;
 mod root;
 mod score;
+mod stack;
 
-pub use api::{search, search_with_eval, SearchInfo};
+pub use api::{search, search_with_eval, search_with_options, PvLine, SearchInfo};
 pub use heuristics::init_lmr;
 pub use limits::SearchLimits;
+pub use options::SearchOptions;
+pub use score::{Score, ScoreBound};
 
 pub(crate) use api::search_with_stop_signal;
+pub(crate) use limits::PonderSignal;
 
 #[cfg(test)]
 mod tests {
     use super::limits::should_stop_next_iteration;
-    use super::score::{checkmate_score, score_from_tt, score_to_tt};
+    use super::score::{checkmate_score, refine_eval_with_tt, score_from_tt, score_to_tt};
     use super::*;
     use crate::tpt::TranspositionTable;
     use crate::Position;
@@ -53,13 +60,13 @@ mod tests {
             )
             .unwrap_or_default();
 
-            let mut tt = TranspositionTable::new_mb(512);
+            let tt = TranspositionTable::new_mb(512);
             init_lmr();
 
             println!("Starting iterative deepening search to depth {}...", depth);
             let start = std::time::Instant::now();
 
-            let result = search(&pos, depth, None, &mut tt);
+            let result = search(&pos, depth, None, &tt);
 
             let duration = start.elapsed();
 
@@ -83,6 +90,103 @@ mod tests {
         });
     }
 
+    #[test]
+    fn principal_variation_is_a_legal_line() {
+        run_with_large_stack(|| {
+            init_lmr();
+
+            let pos = Position::from_fen(
+                "r4rk1/1pp1qppp/p1np1n2/4p3/2BPP1b1/2P2N2/P1P2PPP/R1BQR1K1 w - - 0 1",
+            )
+            .expect("valid FEN");
+            let tt = TranspositionTable::new_mb(16);
+            let info = search_with_eval(
+                &pos,
+                6,
+                SearchLimits::infinite(),
+                &tt,
+                crate::PestoProvider::new(),
+            )
+            .expect("search result");
+
+            assert!(info.pv.len() > 1);
+            assert_eq!(info.pv[0], info.best_move);
+
+            let mut line = pos.clone();
+            for mv in &info.pv {
+                let mut legal = crate::MoveCollector::new();
+                line.generate_moves(&mut legal);
+                assert!(
+                    legal.as_slice().contains(mv),
+                    "illegal pv move {}",
+                    mv.to_uci()
+                );
+                line.make_move(*mv);
+            }
+        });
+    }
+
+    #[test]
+    fn multipv_reports_distinct_ranked_lines() {
+        run_with_large_stack(|| {
+            init_lmr();
+
+            let pos = Position::new();
+            let tt = TranspositionTable::new_mb(16);
+            let info = search_with_options(
+                &pos,
+                5,
+                SearchLimits::infinite(),
+                &SearchOptions::new().with_multi_pv(3),
+                &tt,
+                crate::PestoProvider::new(),
+            )
+            .expect("search result");
+
+            assert_eq!(info.lines.len(), 3);
+            assert_eq!(info.lines[0].pv, info.pv);
+            assert_eq!(info.lines[0].score, info.score);
+
+            let roots: Vec<_> = info.lines.iter().map(|line| line.pv[0]).collect();
+            assert!(roots[0] != roots[1] && roots[1] != roots[2] && roots[0] != roots[2]);
+
+            for pair in info.lines.windows(2) {
+                let (Score::Cp(a), Score::Cp(b)) = (pair[0].score, pair[1].score) else {
+                    panic!("unexpected mate score from the start position");
+                };
+                assert!(a >= b);
+            }
+        });
+    }
+
+    #[test]
+    fn lazy_smp_aggregates_helper_nodes() {
+        run_with_large_stack(|| {
+            init_lmr();
+
+            let pos = Position::from_fen(
+                "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            )
+            .expect("valid FEN");
+            let tt = TranspositionTable::new_mb(16);
+            let info = search_with_options(
+                &pos,
+                6,
+                SearchLimits::infinite(),
+                &SearchOptions::new().with_threads(3),
+                &tt,
+                crate::PestoProvider::new(),
+            )
+            .expect("search result");
+
+            let mut legal = crate::MoveCollector::new();
+            pos.generate_moves(&mut legal);
+            assert!(legal.as_slice().contains(&info.best_move));
+            assert_eq!(info.depth, 6);
+            assert!(info.nodes > 0);
+        });
+    }
+
     #[test]
     fn tt_mate_scores_roundtrip_across_ply() {
         let mate_in_three = 48_997;
@@ -94,6 +198,161 @@ mod tests {
         assert_eq!(score_from_tt(stored, 6), getting_mated);
     }
 
+    #[test]
+    fn qsearch_store_keeps_deeper_tt_entry() {
+        use super::context::{SearchContext, SearchStats, ThreadCounters};
+        use super::params::INFINITY;
+        use crate::tpt::LOWER_BOUND;
+        use crate::{Move, PestoProvider};
+        use std::sync::Arc;
+
+        let mut pos = Position::new();
+        let tt = TranspositionTable::new_mb(1);
+        let eval = PestoProvider::new();
+
+        // a deep lower bound below the window: qsearch cannot cut on it, and stand pat raises alpha.
+        tt.store(pos.hash(), Move(0), -500, None, 10, LOWER_BOUND, true);
+
+        let stats = SearchStats::new(
+            None,
+            Instant::now(),
+            None,
+            None,
+            None,
+            Arc::new(ThreadCounters::new(1)),
+            0,
+        );
+        let mut ctx = SearchContext::new(&pos, &eval, &tt, stats);
+        qsearch::qsearch(&mut pos, &mut ctx, -INFINITY, INFINITY, 0);
+
+        let entry = tt.probe(pos.hash()).expect("deep entry should survive");
+        assert_eq!(entry.depth, 10);
+        assert_eq!(entry.flag, LOWER_BOUND);
+        assert!(entry.is_pv);
+    }
+
+    #[test]
+    fn tt_bounds_refine_static_eval() {
+        use crate::tpt::{EXACT, LOWER_BOUND, UPPER_BOUND};
+
+        assert_eq!(refine_eval_with_tt(20, -15, EXACT), -15);
+        assert_eq!(refine_eval_with_tt(20, 80, LOWER_BOUND), 80);
+        assert_eq!(refine_eval_with_tt(20, -40, LOWER_BOUND), 20);
+        assert_eq!(refine_eval_with_tt(20, -40, UPPER_BOUND), -40);
+        assert_eq!(refine_eval_with_tt(20, 80, UPPER_BOUND), 20);
+        assert_eq!(refine_eval_with_tt(20, 48_990, EXACT), 20);
+    }
+
+    #[test]
+    fn mate_scores_report_moves_to_mate() {
+        assert_eq!(Score::from_internal(-checkmate_score(1)), Score::Mate(1));
+        assert_eq!(Score::from_internal(-checkmate_score(3)), Score::Mate(2));
+        assert_eq!(Score::from_internal(checkmate_score(2)), Score::Mate(-1));
+        assert_eq!(Score::from_internal(checkmate_score(4)), Score::Mate(-2));
+        assert_eq!(Score::from_internal(48), Score::Cp(48));
+        assert_eq!(Score::from_internal(-48_997).to_string(), "mate -1");
+    }
+
+    #[test]
+    fn search_reports_forced_mate() {
+        run_with_large_stack(|| {
+            init_lmr();
+
+            let pos =
+                Position::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").expect("valid FEN");
+            let tt = TranspositionTable::new_mb(16);
+            let info = search_with_eval(
+                &pos,
+                4,
+                SearchLimits::infinite(),
+                &tt,
+                crate::PestoProvider::new(),
+            )
+            .expect("search result");
+
+            assert_eq!(info.score, Score::Mate(1));
+            assert_eq!(info.best_move.to_uci(), "a1a8");
+        });
+    }
+
+    #[test]
+    fn mate_limit_stops_once_mate_is_proven() {
+        run_with_large_stack(|| {
+            init_lmr();
+
+            let pos =
+                Position::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").expect("valid FEN");
+            let tt = TranspositionTable::new_mb(16);
+            let info = search_with_eval(
+                &pos,
+                64,
+                SearchLimits::infinite().with_mate(1),
+                &tt,
+                crate::PestoProvider::new(),
+            )
+            .expect("search result");
+
+            assert_eq!(info.score, Score::Mate(1));
+            assert!(info.depth < 64);
+        });
+    }
+
+    #[test]
+    fn node_limit_caps_search() {
+        run_with_large_stack(|| {
+            init_lmr();
+
+            let tt = TranspositionTable::new_mb(16);
+            let info = search_with_eval(
+                &Position::new(),
+                64,
+                SearchLimits::infinite().with_nodes(20_000),
+                &tt,
+                crate::PestoProvider::new(),
+            )
+            .expect("search result");
+
+            assert!(info.nodes <= 20_000);
+            assert!(info.depth > 0);
+        });
+    }
+
+    #[test]
+    fn search_moves_restrict_the_root() {
+        run_with_large_stack(|| {
+            init_lmr();
+
+            let pos = Position::new();
+            let mut legal = crate::MoveCollector::new();
+            pos.generate_moves(&mut legal);
+            let restricted: Vec<_> = legal
+                .as_slice()
+                .iter()
+                .copied()
+                .filter(|mv| mv.to_uci() == "a2a3" || mv.to_uci() == "h2h4")
+                .collect();
+
+            let tt = TranspositionTable::new_mb(16);
+            let info = search_with_options(
+                &pos,
+                5,
+                SearchLimits::infinite(),
+                &SearchOptions::new()
+                    .with_multi_pv(4)
+                    .with_search_moves(restricted.clone()),
+                &tt,
+                crate::PestoProvider::new(),
+            )
+            .expect("search result");
+
+            assert_eq!(info.lines.len(), 2);
+            assert!(info
+                .lines
+                .iter()
+                .all(|line| restricted.contains(&line.pv[0])));
+        });
+    }
+
     #[test]
     fn checkmate_scores_prefer_shorter_lines() {
         assert!(checkmate_score(1) < checkmate_score(5));
@@ -129,4 +388,62 @@ mod tests {
             50
         ));
     }
+
+    #[test]
+    fn ponder_wait_returns_once_released() {
+        use std::sync::Arc;
+
+        let ponder = Arc::new(PonderSignal::new());
+        let waiters: Vec<_> = (0..2)
+            .map(|_| {
+                let ponder = ponder.clone();
+                thread::spawn(move || ponder.wait_for_release())
+            })
+            .collect();
+
+        ponder.ponderhit();
+        for waiter in waiters {
+            waiter.join().expect("waiter thread panicked");
+        }
+
+        // an already released signal never blocks.
+        ponder.wait_for_release();
+        assert!(!ponder.is_pondering());
+    }
+
+    #[test]
+    fn ponder_search_ignores_clock_until_ponderhit() {
+        run_with_large_stack(|| {
+            use std::sync::atomic::AtomicBool;
+            use std::sync::Arc;
+
+            init_lmr();
+
+            // a zero movetime is already exhausted, so only the depth cap can end a ponder search.
+            let ponder_search = |ponder: Arc<PonderSignal>| {
+                let tt = TranspositionTable::new_mb(16);
+                search_with_stop_signal(
+                    &Position::new(),
+                    6,
+                    SearchLimits::movetime(0),
+                    &SearchOptions::new(),
+                    &tt,
+                    Arc::new(AtomicBool::new(false)),
+                    Some(ponder),
+                    crate::PestoProvider::new(),
+                )
+                .expect("search result")
+            };
+
+            let ponder = Arc::new(PonderSignal::new());
+            let info = ponder_search(ponder.clone());
+            assert_eq!(info.depth, 6);
+            assert!(ponder.is_pondering());
+
+            // after ponderhit the same exhausted clock stops the search before its depth cap.
+            ponder.ponderhit();
+            let info = ponder_search(ponder);
+            assert!(info.depth < 6);
+        });
+    }
 }
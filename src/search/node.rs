@@ -1,17 +1,19 @@
 use super::context::SearchContext;
 use super::features;
 use super::heuristics::{
-    calculate_lmr_reduction, can_use_futility_pruning, can_use_reverse_futility,
-    get_futility_margin, get_rfp_margin, should_prune_futility, should_reduce_lmr,
-    should_rfp_prune, try_iid, try_null_move_pruning, try_probcut, try_razoring,
+    calculate_lmr_reduction, can_use_futility_pruning, can_use_late_move_pruning,
+    can_use_reverse_futility, get_futility_margin, get_lmp_threshold, get_rfp_margin,
+    should_prune_futility, should_prune_late_move, should_reduce_lmr, should_rfp_prune, try_iid,
+    try_null_move_pruning, try_probcut, try_razoring,
 };
-use super::ordering::{pick_next_move, score_move};
-use super::params::{INFINITY, MAX_MOVES};
+use super::ordering::{MovePicker, TriedMoves};
+use super::params::INFINITY;
 use super::qsearch::qsearch;
-use super::score::{checkmate_score, score_from_tt, score_to_tt};
+use super::score::{checkmate_score, refine_eval_with_tt, score_from_tt, score_to_tt};
+use super::stack::PieceTo;
 use crate::eval::EvalProvider;
 use crate::tpt::{EXACT, LOWER_BOUND, UPPER_BOUND};
-use crate::{Move, MoveCollector, Position};
+use crate::{Move, MoveType, Position};
 
 #[derive(Clone, Copy)]
 pub(crate) struct NodeState {
@@ -54,12 +56,15 @@ fn search_with_lmr<E: EvalProvider>(
     gives_check: bool,
     check_extension: u8,
     node: NodeState,
+    tt_pv: bool,
+    improving: bool,
 ) -> i32 {
     // lmr: reduce late moves first, then re-search at full depth only if they improve alpha.
     let do_lmr = should_reduce_lmr(depth, move_index, in_check, gives_check, mv);
 
     if do_lmr {
-        let reduction = calculate_lmr_reduction(depth, move_index, node.pv_node, mv);
+        let reduction =
+            calculate_lmr_reduction(depth, move_index, node.pv_node || tt_pv, improving, mv);
         let reduced_depth = depth
             .saturating_sub(1 + reduction)
             .saturating_add(check_extension);
@@ -114,6 +119,8 @@ fn search_with_pvs<E: EvalProvider>(
     gives_check: bool,
     check_extension: u8,
     node: NodeState,
+    tt_pv: bool,
+    improving: bool,
     is_hash_move: bool,
 ) -> i32 {
     if move_index == 0 {
@@ -132,7 +139,9 @@ fn search_with_pvs<E: EvalProvider>(
     let do_lmr = should_reduce_lmr(depth, move_index, in_check, gives_check, mv) && !is_hash_move;
 
     let mut score = if do_lmr {
-        let reduction = calculate_lmr_reduction(depth, move_index, node.pv_node, mv);
+        // tt pv: positions that were once on the pv are reduced like pv nodes.
+        let reduction =
+            calculate_lmr_reduction(depth, move_index, node.pv_node || tt_pv, improving, mv);
         let reduced_depth = depth
             .saturating_sub(1 + reduction)
             .saturating_add(check_extension);
@@ -180,18 +189,19 @@ pub(crate) fn search_node<E: EvalProvider>(
     beta: i32,
     node: NodeState,
 ) -> i32 {
-    ctx.stats.nodes += 1;
-    let alpha_start = alpha;
-
-    if ctx.stats.should_stop() {
+    if !ctx.stats.enter_node() {
         return 0;
     }
 
+    ctx.pv.clear(node.ply);
+    let alpha_start = alpha;
+
     if pos.is_fifty_move_draw() || (node.ply > 0 && pos.is_repetition()) {
         return 0;
     }
 
     // tt cutoffs: reuse a cached bound or exact score before doing any deeper work.
+    // pv nodes skip the cutoff so the principal variation is never truncated by a hash hit.
     let hash = pos.hash();
     let tt_entry = if features::TT_CUTOFFS {
         ctx.tt.probe(hash).map(|mut entry| {
@@ -203,7 +213,7 @@ pub(crate) fn search_node<E: EvalProvider>(
     };
 
     let tt_move = if let Some(entry) = tt_entry {
-        if entry.depth >= depth && node.excluded_move.is_none() {
+        if entry.depth >= depth && !node.pv_node && node.excluded_move.is_none() {
             ctx.stats.tt_hits += 1;
             match entry.flag {
                 EXACT => return entry.score,
@@ -212,48 +222,62 @@ pub(crate) fn search_node<E: EvalProvider>(
                 _ => {}
             }
         }
-        Some(entry.best_move)
+        // tt move validation: a hash collision may hand back a move from another position.
+        Some(entry.best_move).filter(|&mv| pos.is_legal(mv))
     } else {
         None
     };
 
     // quiescence search: stop full-width recursion and resolve tactical noise with captures only.
     if depth == 0 {
-        return qsearch(pos, ctx, alpha, beta, 0);
+        return qsearch(pos, ctx, alpha, beta, node.ply);
     }
 
     let in_check = pos.is_in_check();
-    let static_eval = ctx.eval.eval(pos, &mut ctx.eval_state);
+    let tt_pv = node.pv_node || tt_entry.is_some_and(|entry| entry.is_pv);
+
+    // tt eval: reuse the cached static eval instead of re-running the evaluator,
+    // then tighten it with the stored score bound for the pruning decisions below.
+    let raw_eval = match tt_entry.and_then(|entry| entry.static_eval) {
+        Some(eval) => eval,
+        None => ctx.eval.eval(pos, &mut ctx.eval_state),
+    };
+    // correction history: the tt keeps the raw eval, since the learned correction keeps moving.
+    let corrected_eval = ctx.correction.correct(pos, raw_eval);
+    let static_eval = tt_entry.map_or(corrected_eval, |entry| {
+        refine_eval_with_tt(corrected_eval, entry.score, entry.flag)
+    });
+
+    // search stack: compare with the eval two plies back to see whether this side is improving.
+    let stack_eval = (!in_check).then_some(corrected_eval);
+    ctx.stack.set_static_eval(node.ply, stack_eval);
+    let improving = ctx.stack.is_improving(node.ply, stack_eval);
 
     // forward pruning: try cheap cutoffs before generating and searching every move.
     if let Some(score) = try_probcut(pos, ctx, depth, beta, node.pv_node, in_check, node.ply) {
         return score;
     }
 
-    if let Some(score) = try_razoring(pos, ctx, depth, alpha, in_check, node.pv_node, static_eval) {
+    if let Some(score) = try_razoring(pos, ctx, depth, alpha, node, in_check, static_eval) {
         return score;
     }
 
     if can_use_reverse_futility(depth, in_check, node.pv_node, beta) {
-        let rfp_margin = get_rfp_margin(depth);
+        let rfp_margin = get_rfp_margin(depth, improving);
         if should_rfp_prune(static_eval, beta, rfp_margin) {
             return static_eval - rfp_margin;
         }
     }
 
-    if let Some(score) = try_null_move_pruning(
-        pos,
-        ctx,
-        depth,
-        beta,
-        node.allow_null,
-        in_check,
-        static_eval,
-        node.ply,
-    ) {
+    if let Some(score) = try_null_move_pruning(pos, ctx, depth, beta, node, in_check, static_eval) {
         return score;
     }
 
+    // extensions: stop extending once the path holds one extension per ply, so a line can at
+    // most double its nominal depth.
+    let can_extend = (ctx.stack.extensions(node.ply) as usize) < node.ply;
+    let mut singular_extension = 0;
+
     // singular extensions: extend depth when the tt move looks uniquely stronger than alternatives.
     if features::SINGULAR_EXTENSIONS
         && !node.pv_node
@@ -261,6 +285,7 @@ pub(crate) fn search_node<E: EvalProvider>(
         && depth >= 8
         && tt_move.is_some()
         && !in_check
+        && can_extend
     {
         if let Some(entry) = tt_entry {
             if entry.depth >= depth.saturating_sub(3) && entry.flag == LOWER_BOUND {
@@ -277,6 +302,7 @@ pub(crate) fn search_node<E: EvalProvider>(
                 );
 
                 if score < singular_beta {
+                    singular_extension = 1;
                     depth += 1;
                 } else if score >= beta {
                     return singular_beta;
@@ -286,18 +312,7 @@ pub(crate) fn search_node<E: EvalProvider>(
     }
 
     // iid: do a shallower search to discover a good tt move when none is cached yet.
-    let iid_move = try_iid(
-        pos,
-        ctx,
-        depth,
-        alpha,
-        beta,
-        node.pv_node,
-        tt_move.is_some(),
-        in_check,
-        node.ply,
-    );
-    let tt_move = tt_move.or(iid_move);
+    let tt_move = tt_move.or_else(|| try_iid(pos, ctx, depth, alpha, beta, node, in_check));
     let tt_order_move = if features::TT_MOVE_ORDERING {
         tt_move
     } else {
@@ -307,59 +322,57 @@ pub(crate) fn search_node<E: EvalProvider>(
     // futility pruning setup: precompute the quiet-move margin used inside the move loop.
     let use_futility = can_use_futility_pruning(depth, in_check, node.pv_node, alpha, beta);
     let (static_eval, futility_margin) = if use_futility {
-        (static_eval, get_futility_margin(depth))
+        (static_eval, get_futility_margin(depth, improving))
     } else {
         (0, 0)
     };
 
-    // move ordering: generate legal moves, then score them for tt/captures/killers/history.
-    let mut collector = MoveCollector::new();
-    pos.generate_moves(&mut collector);
-    let moves = collector.as_slice();
+    // late move pruning setup: how many moves a shallow node tries before it gives up on quiets.
+    let lmp_threshold = can_use_late_move_pruning(depth, in_check, node.pv_node, alpha, beta)
+        .then(|| get_lmp_threshold(depth, improving));
 
-    if moves.is_empty() {
-        return if in_check {
-            checkmate_score(node.ply)
-        } else {
-            0
-        };
-    }
-
-    let move_count = moves.len();
-    let mut move_list = [Move(0); MAX_MOVES];
-    let mut scores = [0i32; MAX_MOVES];
-
-    for i in 0..move_count {
-        move_list[i] = moves[i];
-        scores[i] = score_move(moves[i], pos, tt_order_move, Some(&ctx.history), node.ply);
-    }
+    // move ordering: a staged picker hands out the tt move, captures, killers and history quiets,
+    // generating each batch only when the previous stages failed to cut off.
+    let continuation = ctx.stack.continuation(node.ply);
+    let mut picker = MovePicker::new(tt_order_move, &ctx.history, node.ply, continuation);
 
     // move loop: search moves in order and let alpha-beta cut the rest when possible.
     let mut best_score = -INFINITY;
     let mut best_move = Move(0);
+    let mut move_count = 0;
+    let mut captures_tried = TriedMoves::new();
+    let mut quiets_tried = TriedMoves::new();
+
+    while let Some(mv) = picker.next(pos, &ctx.history) {
+        let i = move_count;
+        move_count += 1;
 
-    for i in 0..move_count {
         if ctx.stats.should_stop() {
             break;
         }
 
-        pick_next_move(&mut move_list[..move_count], &mut scores[..move_count], i);
-        let mv = move_list[i];
-
         if let Some(excluded) = node.excluded_move {
             if mv.0 == excluded.0 {
                 continue;
             }
         }
 
+        // late move pruning: past the threshold, quiets are skipped without even being made.
+        if lmp_threshold.is_some_and(|threshold| should_prune_late_move(mv, i, threshold)) {
+            continue;
+        }
+
+        let moved = PieceTo::new(pos, mv);
         let delta = ctx.eval.update_on_move(&mut ctx.eval_state, pos, mv);
         pos.make_move(mv);
         let gives_check = pos.is_in_check();
-        let check_extension = if features::CHECK_EXTENSIONS && gives_check {
+        let check_extension = if features::CHECK_EXTENSIONS && gives_check && can_extend {
             1
         } else {
             0
         };
+        ctx.stack
+            .set_move(node.ply, Some(moved), singular_extension + check_extension);
 
         // futility pruning: skip quiet non-checking moves that cannot plausibly raise alpha.
         if use_futility
@@ -385,6 +398,8 @@ pub(crate) fn search_node<E: EvalProvider>(
                 gives_check,
                 check_extension,
                 node,
+                tt_pv,
+                improving,
             )
         } else {
             let is_hash_move = tt_order_move.is_some_and(|tt_mv| mv.0 == tt_mv.0);
@@ -400,6 +415,8 @@ pub(crate) fn search_node<E: EvalProvider>(
                 gives_check,
                 check_extension,
                 node,
+                tt_pv,
+                improving,
                 is_hash_move,
             )
         };
@@ -411,38 +428,67 @@ pub(crate) fn search_node<E: EvalProvider>(
             return 0;
         }
 
-        // beta cutoff: once this move is good enough, stop and record the quiet-move heuristics.
+        // beta cutoff: once this move is good enough, stop and record the move-ordering heuristics.
         if score >= beta {
             if !mv.is_capture() && !mv.is_promotion() {
-                // killer moves: store this move as it caused a beta cutoff
-                if features::KILLER_MOVES {
-                    ctx.history.killers.store(node.ply, mv);
-                }
-                if features::HISTORY_HEURISTIC {
-                    let bonus = (depth as i16 * depth as i16).min(400);
-                    ctx.history
-                        .history
-                        .update(pos.side_to_move, mv.from(), mv.to(), bonus);
+                ctx.history.update_quiet_cutoff(
+                    pos,
+                    mv,
+                    quiets_tried.as_slice(),
+                    continuation,
+                    node.ply,
+                    depth,
+                );
+
+                // correction history: a quiet cutoff above the eval shows it undershoots here.
+                if !in_check && node.excluded_move.is_none() && beta > corrected_eval {
+                    ctx.correction.update(pos, depth, beta, raw_eval);
                 }
             }
+            ctx.history
+                .update_captures(pos, mv, captures_tried.as_slice(), depth);
 
             if features::TT_CUTOFFS {
-                ctx.tt
-                    .store(hash, mv, score_to_tt(beta, node.ply), depth, LOWER_BOUND);
+                ctx.tt.store(
+                    hash,
+                    mv,
+                    score_to_tt(beta, node.ply),
+                    Some(raw_eval),
+                    depth,
+                    LOWER_BOUND,
+                    tt_pv,
+                );
             }
             return beta;
         }
 
+        if mv.is_capture() || mv.move_type() == MoveType::EnPassant {
+            captures_tried.push(mv);
+        } else if !mv.is_promotion() {
+            quiets_tried.push(mv);
+        }
+
         if score > best_score {
             best_score = score;
             best_move = mv;
 
             if score > alpha {
                 alpha = score;
+                if node.pv_node {
+                    ctx.pv.update(node.ply, mv);
+                }
             }
         }
     }
 
+    if move_count == 0 {
+        return if in_check {
+            checkmate_score(node.ply)
+        } else {
+            0
+        };
+    }
+
     // tt store: cache the best result from this node as an exact score or upper bound.
     let flag = if best_score <= alpha_start {
         UPPER_BOUND
@@ -450,13 +496,26 @@ pub(crate) fn search_node<E: EvalProvider>(
         EXACT
     };
 
+    // correction history: learn from exact scores, and from upper bounds that fall below the eval.
+    // a capture or promotion as best move wins material the eval was never meant to see.
+    if !in_check
+        && node.excluded_move.is_none()
+        && !best_move.is_capture()
+        && !best_move.is_promotion()
+        && (flag == EXACT || best_score < corrected_eval)
+    {
+        ctx.correction.update(pos, depth, best_score, raw_eval);
+    }
+
     if features::TT_CUTOFFS {
         ctx.tt.store(
             hash,
             best_move,
             score_to_tt(best_score, node.ply),
+            Some(raw_eval),
             depth,
             flag,
+            tt_pv,
         );
     }
 
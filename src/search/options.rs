@@ -0,0 +1,43 @@
+use crate::Move;
+
+/// Search configuration that is independent of depth and time limits.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SearchOptions {
+    /// Number of best root lines to search and report (UCI `MultiPV`).
+    pub multi_pv: usize,
+    /// Number of Lazy SMP search threads, including the main thread (UCI `Threads`).
+    pub threads: usize,
+    /// Root moves to consider (UCI `go searchmoves`); empty means every legal move.
+    pub search_moves: Vec<Move>,
+}
+
+impl SearchOptions {
+    pub const fn new() -> Self {
+        Self {
+            multi_pv: 1,
+            threads: 1,
+            search_moves: Vec::new(),
+        }
+    }
+
+    pub const fn with_multi_pv(mut self, multi_pv: usize) -> Self {
+        self.multi_pv = if multi_pv == 0 { 1 } else { multi_pv };
+        self
+    }
+
+    pub const fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = if threads == 0 { 1 } else { threads };
+        self
+    }
+
+    pub fn with_search_moves(mut self, search_moves: Vec<Move>) -> Self {
+        self.search_moves = search_moves;
+        self
+    }
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,5 +1,10 @@
-use super::params::MAX_DEPTH;
-use crate::{search::features, types::Color, Move, Piece, Position};
+use super::params::{MAX_DEPTH, MAX_MOVES};
+use super::stack::{Continuation, PieceTo};
+use crate::{
+    search::features,
+    types::{Color, MoveType},
+    Move, MoveCollector, Piece, Position,
+};
 
 const KILLERS_PER_PLY: usize = 2;
 const MAX_HISTORY: i32 = 50_000;
@@ -42,6 +47,16 @@ impl KillerTable {
         killers[0].0 == mv.0 || killers[1].0 == mv.0
     }
 
+    /// Returns both killer slots for `ply`, primary first; empty slots hold `Move(0)`.
+    #[inline(always)]
+    pub(crate) fn get(&self, ply: usize) -> [Move; KILLERS_PER_PLY] {
+        if ply >= MAX_DEPTH {
+            return [Move(0); KILLERS_PER_PLY];
+        }
+
+        self.killers[ply]
+    }
+
     #[inline(always)]
     pub(crate) fn get_primary(&self, ply: usize) -> Option<Move> {
         if ply >= MAX_DEPTH {
@@ -63,6 +78,20 @@ impl Default for KillerTable {
     }
 }
 
+/// Adds `bonus`, which may be negative, to a history entry kept within `MAX_HISTORY`.
+#[inline(always)]
+fn apply_history_bonus(entry: &mut i32, bonus: i16) {
+    let bonus = bonus as i32;
+
+    // history gravity: shrink the bonus as the entry nears its bound instead of clamping,
+    // so saturated moves stay responsive to later maluses.
+    if features::HISTORY_GRAVITY {
+        *entry += bonus - *entry * bonus.abs() / MAX_HISTORY;
+    } else {
+        *entry = (*entry + bonus).clamp(-MAX_HISTORY, MAX_HISTORY);
+    }
+}
+
 pub(crate) struct HistoryTable {
     table: [[[i32; 64]; 64]; 2],
 }
@@ -78,7 +107,7 @@ impl HistoryTable {
     #[inline(always)]
     pub(crate) fn update(&mut self, color: Color, from: usize, to: usize, bonus: i16) {
         let entry = &mut self.table[color as usize][from][to];
-        *entry = (*entry + bonus as i32).clamp(-MAX_HISTORY, MAX_HISTORY);
+        apply_history_bonus(entry, bonus);
     }
 
     #[inline(always)]
@@ -93,9 +122,155 @@ impl Default for HistoryTable {
     }
 }
 
+/// Quiet move that refuted each previous move, indexed by that move's piece and destination.
+pub(crate) struct CounterMoveTable {
+    table: [Move; 12 * 64],
+}
+
+impl CounterMoveTable {
+    #[inline(always)]
+    pub(crate) fn new() -> Self {
+        Self {
+            table: [Move(0); 12 * 64],
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn store(&mut self, prev: PieceTo, mv: Move) {
+        self.table[prev.index()] = mv;
+    }
+
+    #[inline(always)]
+    pub(crate) fn get(&self, prev: PieceTo) -> Move {
+        self.table[prev.index()]
+    }
+}
+
+impl Default for CounterMoveTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// History of quiet moves in the context of an earlier move.
+///
+/// Rows are keyed by the earlier move's piece and destination, columns by the
+/// piece and destination of the move being scored. Its colour follows from the
+/// row, since the table is used at a fixed distance from the earlier move.
+pub(crate) struct ContinuationHistory {
+    table: Box<[[[i32; 64]; 6]]>,
+}
+
+impl ContinuationHistory {
+    pub(crate) fn new() -> Self {
+        Self {
+            table: vec![[[0; 64]; 6]; 12 * 64].into_boxed_slice(),
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn update(&mut self, prev: PieceTo, piece: Piece, to: usize, bonus: i16) {
+        let entry = &mut self.table[prev.index()][piece as usize][to];
+        apply_history_bonus(entry, bonus);
+    }
+
+    #[inline(always)]
+    pub(crate) fn get(&self, prev: PieceTo, piece: Piece, to: usize) -> i32 {
+        self.table[prev.index()][piece as usize][to]
+    }
+}
+
+impl Default for ContinuationHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// History of captures, indexed by moving piece, destination and captured piece type.
+pub(crate) struct CaptureHistory {
+    table: [[[i32; 6]; 64]; 12],
+}
+
+impl CaptureHistory {
+    #[inline(always)]
+    pub(crate) fn new() -> Self {
+        Self {
+            table: [[[0; 6]; 64]; 12],
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn update(&mut self, attacker: PieceTo, captured: Piece, bonus: i16) {
+        let entry = &mut self.table[attacker.index() / 64][attacker.to as usize][captured as usize];
+        apply_history_bonus(entry, bonus);
+    }
+
+    #[inline(always)]
+    pub(crate) fn get(&self, attacker: PieceTo, captured: Piece) -> i32 {
+        self.table[attacker.index() / 64][attacker.to as usize][captured as usize]
+    }
+}
+
+impl Default for CaptureHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the piece type `mv` captures, counting en passant as a pawn capture.
+#[inline(always)]
+fn captured_piece(pos: &Position, mv: Move) -> Option<Piece> {
+    if mv.move_type() == MoveType::EnPassant {
+        Some(Piece::Pawn)
+    } else if mv.is_capture() {
+        pos.piece_at(mv.to()).map(|(piece, _)| piece)
+    } else {
+        None
+    }
+}
+
+/// Moves already searched at a node without causing a cutoff, kept for history maluses.
+///
+/// Sized like a generated move list, so every move searched at the node is remembered.
+pub(crate) struct TriedMoves {
+    moves: [Move; MAX_MOVES],
+    len: usize,
+}
+
+impl TriedMoves {
+    #[inline(always)]
+    pub(crate) fn new() -> Self {
+        Self {
+            moves: [Move(0); MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn push(&mut self, mv: Move) {
+        self.moves[self.len] = mv;
+        self.len += 1;
+    }
+
+    #[inline(always)]
+    pub(crate) fn as_slice(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+}
+
+impl Default for TriedMoves {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub(crate) struct MoveHistory {
     pub(crate) killers: KillerTable,
     pub(crate) history: HistoryTable,
+    pub(crate) counters: CounterMoveTable,
+    /// Continuation tables one and two plies back, matching [`Continuation`].
+    pub(crate) continuation: [ContinuationHistory; 2],
+    pub(crate) captures: CaptureHistory,
 }
 
 impl MoveHistory {
@@ -103,6 +278,126 @@ impl MoveHistory {
         Self {
             killers: KillerTable::new(),
             history: HistoryTable::new(),
+            counters: CounterMoveTable::new(),
+            continuation: [ContinuationHistory::new(), ContinuationHistory::new()],
+            captures: CaptureHistory::new(),
+        }
+    }
+
+    /// Capture-history score of `mv`, or 0 for non-captures and when the feature is off.
+    #[inline(always)]
+    pub(crate) fn capture_score(&self, pos: &Position, mv: Move) -> i32 {
+        if !features::CAPTURE_HISTORY {
+            return 0;
+        }
+
+        captured_piece(pos, mv).map_or(0, |captured| {
+            self.captures.get(PieceTo::new(pos, mv), captured)
+        })
+    }
+
+    /// Rewards `best` for a beta cutoff at `depth` if it is a capture, and
+    /// penalizes the captures searched before it that failed to cut off.
+    #[inline(always)]
+    pub(crate) fn update_captures(
+        &mut self,
+        pos: &Position,
+        best: Move,
+        captures_tried: &[Move],
+        depth: u8,
+    ) {
+        // capture history: learn which captures refute positions beyond what see predicts.
+        if !features::CAPTURE_HISTORY {
+            return;
+        }
+
+        let bonus = (depth as i16 * depth as i16).min(400);
+        if let Some(captured) = captured_piece(pos, best) {
+            self.captures
+                .update(PieceTo::new(pos, best), captured, bonus);
+        }
+
+        for &mv in captures_tried {
+            if let Some(captured) = captured_piece(pos, mv) {
+                self.captures
+                    .update(PieceTo::new(pos, mv), captured, -bonus);
+            }
+        }
+    }
+
+    /// Ordering score of a quiet move from the butterfly and continuation histories.
+    #[inline(always)]
+    pub(crate) fn quiet_score(&self, pos: &Position, mv: Move, continuation: Continuation) -> i32 {
+        let mut score = if features::HISTORY_HEURISTIC {
+            self.history.get(pos.side_to_move, mv.from(), mv.to())
+        } else {
+            0
+        };
+
+        // continuation history: reward quiets that worked after the same earlier moves.
+        if features::CONTINUATION_HISTORY {
+            let piece = PieceTo::new(pos, mv).piece;
+            for (table, prev) in self.continuation.iter().zip(continuation) {
+                if let Some(prev) = prev {
+                    score += table.get(prev, piece, mv.to());
+                }
+            }
+        }
+
+        score
+    }
+
+    /// Rewards the quiet move `mv` for causing a beta cutoff at `depth`; with history
+    /// gravity, also penalizes the quiets in `quiets_tried` searched before it.
+    #[inline(always)]
+    pub(crate) fn update_quiet_cutoff(
+        &mut self,
+        pos: &Position,
+        mv: Move,
+        quiets_tried: &[Move],
+        continuation: Continuation,
+        ply: usize,
+        depth: u8,
+    ) {
+        // killer moves: store this move as it caused a beta cutoff
+        if features::KILLER_MOVES {
+            self.killers.store(ply, mv);
+        }
+
+        // counter moves: remember this reply to the previous move.
+        if features::CONTINUATION_HISTORY {
+            if let Some(prev) = continuation[0] {
+                self.counters.store(prev, mv);
+            }
+        }
+
+        let bonus = (depth as i16 * depth as i16).min(400);
+        self.update_quiet(pos, mv, continuation, bonus);
+
+        // history gravity: quiets that were searched and failed to cut off lose what the cutoff gained.
+        if features::HISTORY_GRAVITY {
+            for &tried in quiets_tried {
+                self.update_quiet(pos, tried, continuation, -bonus);
+            }
+        }
+    }
+
+    /// Applies `bonus` to the butterfly and continuation entries of the quiet move `mv`.
+    #[inline(always)]
+    fn update_quiet(&mut self, pos: &Position, mv: Move, continuation: Continuation, bonus: i16) {
+        if features::HISTORY_HEURISTIC {
+            self.history
+                .update(pos.side_to_move, mv.from(), mv.to(), bonus);
+        }
+
+        // continuation history: score this move in the context of the moves before it.
+        if features::CONTINUATION_HISTORY {
+            let piece = PieceTo::new(pos, mv).piece;
+            for (table, prev) in self.continuation.iter_mut().zip(continuation) {
+                if let Some(prev) = prev {
+                    table.update(prev, piece, mv.to(), bonus);
+                }
+            }
         }
     }
 }
@@ -118,15 +413,20 @@ const SCORE_GOOD_CAPTURE: i32 = 100_000;
 pub(crate) const SCORE_PROMOTION: i32 = 90_000;
 const SCORE_KILLER_PRIMARY: i32 = 20_000;
 const SCORE_KILLER_SECONDARY: i32 = 15_000;
+const SCORE_COUNTER_MOVE: i32 = 10_000;
 const SCORE_BAD_CAPTURE: i32 = 5_000;
 const PIECE_VALUES: [i32; 6] = [100, 320, 330, 500, 900, 20_000];
+/// Capture history is scaled down by this much so it reorders captures with
+/// similar SEE without ever moving one across the good/bad capture split.
+const CAPTURE_HISTORY_DIVISOR: i32 = 16;
 
 #[inline(always)]
-pub(crate) const fn score_capture_from_see(see_score: i32) -> i32 {
+pub(crate) const fn score_capture_from_see(see_score: i32, capture_history: i32) -> i32 {
+    let learned = capture_history / CAPTURE_HISTORY_DIVISOR;
     if see_score >= 0 {
-        SCORE_GOOD_CAPTURE + see_score
+        SCORE_GOOD_CAPTURE + see_score + learned
     } else {
-        SCORE_BAD_CAPTURE + see_score
+        SCORE_BAD_CAPTURE + see_score + learned
     }
 }
 
@@ -147,10 +447,11 @@ fn score_capture_from_mvv_lva(m: Move, pos: &Position) -> i32 {
 }
 
 #[inline(always)]
-pub(crate) fn score_capture(m: Move, pos: &Position) -> i32 {
+pub(crate) fn score_capture(m: Move, pos: &Position, history: Option<&MoveHistory>) -> i32 {
     // see capture ordering: prefer captures that win material after the full exchange sequence.
     if features::SEE {
-        score_capture_from_see(pos.see(&m))
+        let capture_history = history.map_or(0, |h| h.capture_score(pos, m));
+        score_capture_from_see(pos.see(&m), capture_history)
     } else {
         score_capture_from_mvv_lva(m, pos)
     }
@@ -163,8 +464,9 @@ pub(crate) fn score_move(
     tt_move: Option<Move>,
     history: Option<&MoveHistory>,
     ply: usize,
+    continuation: Continuation,
 ) -> i32 {
-    // move ordering: tt move first, then captures/promotions, then killer and history quiets.
+    // move ordering: tt move first, then captures/promotions, then killer, counter and history quiets.
     if features::TT_MOVE_ORDERING {
         if let Some(tt_mv) = tt_move {
             if m.0 == tt_mv.0 {
@@ -174,7 +476,7 @@ pub(crate) fn score_move(
     }
 
     if m.is_capture() {
-        return score_capture(m, pos);
+        return score_capture(m, pos, history);
     }
 
     if m.is_promotion() {
@@ -190,9 +492,13 @@ pub(crate) fn score_move(
             };
         }
 
-        if features::HISTORY_HEURISTIC {
-            return h.history.get(pos.side_to_move, m.from(), m.to());
+        if features::CONTINUATION_HISTORY
+            && continuation[0].is_some_and(|prev| h.counters.get(prev) == m)
+        {
+            return SCORE_COUNTER_MOVE;
         }
+
+        return h.quiet_score(pos, m, continuation);
     }
 
     0
@@ -220,3 +526,429 @@ pub(crate) fn pick_next_move(moves: &mut [Move], scores: &mut [i32], index: usiz
         scores.swap(index, best_idx);
     }
 }
+
+/// Lowest score a generated capture may have and still be tried before the quiets.
+///
+/// With SEE ordering, losing captures score below every winning capture and
+/// promotion and are held back until the end. MVV-LVA has no notion of a losing
+/// capture, so every capture counts as good.
+const GOOD_CAPTURE_MIN: i32 = if features::SEE {
+    SCORE_PROMOTION
+} else {
+    i32::MIN
+};
+
+/// Returns `true` for moves produced by [`Position::generate_captures`].
+#[inline(always)]
+fn is_noisy(mv: Move) -> bool {
+    matches!(
+        mv.move_type(),
+        MoveType::Capture
+            | MoveType::EnPassant
+            | MoveType::PromotionQueen
+            | MoveType::CapturePromotionQueen
+    )
+}
+
+/// Killer slots plus one counter move.
+const REFUTATIONS: usize = KILLERS_PER_PLY + 1;
+
+/// Stages of a [`MovePicker`], in the order their moves are handed out.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    TtMove,
+    GenerateCaptures,
+    GoodCaptures,
+    Refutations,
+    GenerateQuiets,
+    Quiets,
+    BadCaptures,
+    Done,
+}
+
+/// One generated batch of moves with their ordering scores.
+struct ScoredMoves {
+    moves: [Move; MAX_MOVES],
+    scores: [i32; MAX_MOVES],
+    len: usize,
+    index: usize,
+}
+
+impl ScoredMoves {
+    #[inline(always)]
+    fn new() -> Self {
+        Self {
+            moves: [Move(0); MAX_MOVES],
+            scores: [0; MAX_MOVES],
+            len: 0,
+            index: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn push(&mut self, mv: Move, score: i32) {
+        self.moves[self.len] = mv;
+        self.scores[self.len] = score;
+        self.len += 1;
+    }
+
+    /// Hands out the best remaining move, or `None` once the best left scores
+    /// below `min_score`. Moves left behind stay available for a later call.
+    #[inline(always)]
+    fn next_at_least(&mut self, min_score: i32) -> Option<Move> {
+        if self.index >= self.len {
+            return None;
+        }
+
+        pick_next_move(
+            &mut self.moves[..self.len],
+            &mut self.scores[..self.len],
+            self.index,
+        );
+        if self.scores[self.index] < min_score {
+            return None;
+        }
+
+        self.index += 1;
+        Some(self.moves[self.index - 1])
+    }
+}
+
+/// Staged move ordering for `search_node` and `qsearch`.
+///
+/// Moves are produced lazily: the TT move, then winning captures and queen
+/// promotions by score, then killers and the counter move, then quiet moves by
+/// history, and finally losing captures. Each stage only generates what it needs, so a TT move or a
+/// good capture that cuts off saves generating and scoring the quiet moves.
+///
+/// The TT move, killers and counter move are checked with [`Position::is_legal`] before being
+/// handed out, and skipped when they reappear in a generated stage, so every
+/// legal move is returned exactly once. The quiescence picker stops after the
+/// good captures.
+pub(crate) struct MovePicker {
+    stage: Stage,
+    tt_move: Option<Move>,
+    /// Killers followed by the counter move, with `Move(0)` in unused slots.
+    refutations: [Move; REFUTATIONS],
+    refutation_index: usize,
+    continuation: Continuation,
+    captures_only: bool,
+    captures: ScoredMoves,
+    quiets: ScoredMoves,
+}
+
+impl MovePicker {
+    /// Picker over every legal move at `ply`, reached through `continuation`, for the main search.
+    #[inline(always)]
+    pub(crate) fn new(
+        tt_move: Option<Move>,
+        history: &MoveHistory,
+        ply: usize,
+        continuation: Continuation,
+    ) -> Self {
+        let mut refutations = [Move(0); REFUTATIONS];
+
+        // killer moves: quiets that cut off at this ply elsewhere are tried before the rest.
+        if features::KILLER_MOVES {
+            refutations[..KILLERS_PER_PLY].copy_from_slice(&history.killers.get(ply));
+        }
+
+        // counter move: the quiet that last refuted the opponent's previous move.
+        if features::CONTINUATION_HISTORY {
+            if let Some(prev) = continuation[0] {
+                let counter = history.counters.get(prev);
+                if !refutations.contains(&counter) {
+                    refutations[KILLERS_PER_PLY] = counter;
+                }
+            }
+        }
+
+        Self {
+            stage: Stage::TtMove,
+            tt_move,
+            refutations,
+            refutation_index: 0,
+            continuation,
+            captures_only: false,
+            captures: ScoredMoves::new(),
+            quiets: ScoredMoves::new(),
+        }
+    }
+
+    /// Picker over the captures and queen promotions worth searching in quiescence.
+    ///
+    /// A TT move is only tried when it is one of those tactical moves.
+    #[inline(always)]
+    pub(crate) fn qsearch(tt_move: Option<Move>) -> Self {
+        Self {
+            tt_move: tt_move.filter(|&mv| is_noisy(mv)),
+            captures_only: true,
+            stage: Stage::TtMove,
+            refutations: [Move(0); REFUTATIONS],
+            refutation_index: 0,
+            continuation: [None; 2],
+            captures: ScoredMoves::new(),
+            quiets: ScoredMoves::new(),
+        }
+    }
+
+    #[inline(always)]
+    fn is_tt_move(&self, mv: Move) -> bool {
+        self.tt_move.is_some_and(|tt_mv| tt_mv.0 == mv.0)
+    }
+
+    /// Returns the next move to search, or `None` once every stage is exhausted.
+    pub(crate) fn next(&mut self, pos: &Position, history: &MoveHistory) -> Option<Move> {
+        loop {
+            match self.stage {
+                Stage::TtMove => {
+                    self.stage = Stage::GenerateCaptures;
+                    if let Some(mv) = self.tt_move.filter(|&mv| pos.is_legal(mv)) {
+                        return Some(mv);
+                    }
+                    self.tt_move = None;
+                }
+                Stage::GenerateCaptures => {
+                    let mut collector = MoveCollector::new();
+                    pos.generate_captures(&mut collector);
+                    for &mv in collector.as_slice() {
+                        if self.is_tt_move(mv) {
+                            continue;
+                        }
+
+                        let score = if mv.move_type() == MoveType::PromotionQueen {
+                            SCORE_PROMOTION
+                        } else {
+                            score_capture(mv, pos, Some(history))
+                        };
+                        self.captures.push(mv, score);
+                    }
+                    self.stage = Stage::GoodCaptures;
+                }
+                Stage::GoodCaptures => {
+                    if let Some(mv) = self.captures.next_at_least(GOOD_CAPTURE_MIN) {
+                        return Some(mv);
+                    }
+                    self.stage = if self.captures_only {
+                        Stage::Done
+                    } else {
+                        Stage::Refutations
+                    };
+                }
+                Stage::Refutations => {
+                    while self.refutation_index < REFUTATIONS {
+                        let mv = self.refutations[self.refutation_index];
+                        self.refutation_index += 1;
+
+                        if mv.0 != 0 && !self.is_tt_move(mv) && !is_noisy(mv) && pos.is_legal(mv) {
+                            return Some(mv);
+                        }
+                        self.refutations[self.refutation_index - 1] = Move(0);
+                    }
+                    self.stage = Stage::GenerateQuiets;
+                }
+                Stage::GenerateQuiets => {
+                    let mut collector = MoveCollector::new();
+                    pos.generate_quiets(&mut collector);
+                    for &mv in collector.as_slice() {
+                        if self.is_tt_move(mv) || self.refutations.contains(&mv) {
+                            continue;
+                        }
+
+                        self.quiets
+                            .push(mv, history.quiet_score(pos, mv, self.continuation));
+                    }
+                    self.stage = Stage::Quiets;
+                }
+                Stage::Quiets => {
+                    if let Some(mv) = self.quiets.next_at_least(i32::MIN) {
+                        return Some(mv);
+                    }
+                    self.stage = Stage::BadCaptures;
+                }
+                Stage::BadCaptures => {
+                    if let Some(mv) = self.captures.next_at_least(i32::MIN) {
+                        return Some(mv);
+                    }
+                    self.stage = Stage::Done;
+                }
+                Stage::Done => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MoveHistory, MovePicker, TriedMoves, MAX_HISTORY};
+    use crate::search::{features, stack::PieceTo};
+    use crate::types::Color;
+    use crate::{Move, MoveCollector, MoveType, Piece, Position};
+
+    fn drain(mut picker: MovePicker, pos: &Position, history: &MoveHistory) -> Vec<Move> {
+        std::iter::from_fn(|| picker.next(pos, history)).collect()
+    }
+
+    #[test]
+    fn picker_returns_each_legal_move_once_in_stage_order() {
+        // kiwipete: winning and losing captures, castling and plenty of quiets.
+        let pos = Position::from_fen(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        )
+        .expect("valid FEN");
+        let mut history = MoveHistory::new();
+        let killer = Move::new(4, 3, MoveType::Quiet);
+        history.killers.store(3, killer);
+        let tt_move = Move::new(4, 6, MoveType::Castle);
+
+        let picked = drain(
+            MovePicker::new(Some(tt_move), &history, 3, [None; 2]),
+            &pos,
+            &history,
+        );
+
+        let mut collector = MoveCollector::new();
+        pos.generate_moves(&mut collector);
+        let mut legal = collector.as_slice().to_vec();
+        let mut sorted = picked.clone();
+        legal.sort_by_key(|mv| mv.0);
+        sorted.sort_by_key(|mv| mv.0);
+        assert_eq!(sorted, legal);
+
+        assert_eq!(picked[0], tt_move);
+        if !features::KILLER_MOVES || !features::SEE {
+            return;
+        }
+        let first_quiet = 1 + picked[1..]
+            .iter()
+            .position(|mv| !mv.is_capture() && !mv.is_promotion())
+            .expect("quiet moves exist");
+        assert_eq!(picked[first_quiet], killer);
+        assert!(picked[1..first_quiet].iter().all(|mv| pos.see(mv) >= 0));
+    }
+
+    #[test]
+    fn picker_rejects_moves_that_do_not_fit_the_position() {
+        let pos = Position::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").expect("valid FEN");
+        let mut history = MoveHistory::new();
+        // a stale killer onto an occupied square and a collided tt move from another game.
+        history.killers.store(0, Move::new(4, 12, MoveType::Quiet));
+        let bogus_tt = Move::new(1, 18, MoveType::Quiet);
+
+        let picked = drain(
+            MovePicker::new(Some(bogus_tt), &history, 0, [None; 2]),
+            &pos,
+            &history,
+        );
+
+        let mut collector = MoveCollector::new();
+        pos.generate_moves(&mut collector);
+        assert_eq!(picked.len(), collector.len());
+        assert!(picked.iter().all(|&mv| pos.is_legal(mv)));
+    }
+
+    #[test]
+    fn qsearch_picker_skips_quiets_and_losing_captures() {
+        if !features::SEE {
+            return;
+        }
+
+        // the queen can take a defended pawn on d5 or an undefended knight on g5.
+        let pos = Position::from_fen("4k3/8/4p3/3p2n1/8/8/3Q4/4K3 w - - 0 1").expect("valid FEN");
+        let history = MoveHistory::new();
+        let quiet_tt = Move::new(11, 19, MoveType::Quiet);
+
+        let picked = drain(MovePicker::qsearch(Some(quiet_tt)), &pos, &history);
+
+        assert_eq!(picked, vec![Move::new(11, 38, MoveType::Capture)]);
+    }
+
+    #[test]
+    fn cutoff_reply_becomes_counter_move_and_scores_in_context() {
+        if !features::CONTINUATION_HISTORY {
+            return;
+        }
+
+        // after 1.e4 the knight reply cuts off, so it should lead the quiets next time.
+        let pos = Position::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1")
+            .expect("valid FEN");
+        let e4 = PieceTo {
+            piece: Piece::Pawn,
+            color: Color::White,
+            to: 28,
+        };
+        let reply = Move::new(57, 42, MoveType::Quiet);
+        let mut history = MoveHistory::new();
+
+        history.update_quiet_cutoff(&pos, reply, &[], [Some(e4), None], 1, 6);
+
+        assert_eq!(history.counters.get(e4), reply);
+        assert!(
+            history.quiet_score(&pos, reply, [Some(e4), None])
+                > history.quiet_score(&pos, reply, [None; 2])
+        );
+
+        let picked = drain(
+            MovePicker::new(None, &history, 7, [Some(e4), None]),
+            &pos,
+            &history,
+        );
+        assert_eq!(picked[0], reply);
+    }
+
+    #[test]
+    fn capture_history_breaks_ties_between_equal_see_captures() {
+        if !features::CAPTURE_HISTORY {
+            return;
+        }
+
+        // both rooks win an undefended knight, so see alone cannot tell them apart.
+        let pos = Position::from_fen("4k3/8/8/2n3n1/8/8/8/2R1K1R1 w - - 0 1").expect("valid FEN");
+        let c_file = Move::new(2, 34, MoveType::Capture);
+        let g_file = Move::new(6, 38, MoveType::Capture);
+
+        for (best, failed) in [(c_file, g_file), (g_file, c_file)] {
+            let mut history = MoveHistory::new();
+            history.update_captures(&pos, best, &[failed], 10);
+            let picked = drain(MovePicker::qsearch(None), &pos, &history);
+            assert_eq!(picked, vec![best, failed]);
+        }
+    }
+
+    #[test]
+    fn tried_moves_remember_every_move_of_a_full_move_list() {
+        // the most legal moves any position has.
+        let moves: Vec<Move> = (0..218u16).map(Move).collect();
+        let mut tried = TriedMoves::new();
+        for &mv in &moves {
+            tried.push(mv);
+        }
+        assert_eq!(tried.as_slice(), moves.as_slice());
+    }
+
+    #[test]
+    fn gravity_penalizes_failed_quiets_and_saturates_below_the_bound() {
+        if !features::HISTORY_GRAVITY || !features::HISTORY_HEURISTIC {
+            return;
+        }
+
+        let pos = Position::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")
+            .expect("valid FEN");
+        let best = Move::new(6, 21, MoveType::Quiet);
+        let failed = Move::new(1, 18, MoveType::Quiet);
+        let mut history = MoveHistory::new();
+
+        history.update_quiet_cutoff(&pos, best, &[failed], [None; 2], 0, 10);
+        assert!(history.quiet_score(&pos, best, [None; 2]) > 0);
+        assert!(history.quiet_score(&pos, failed, [None; 2]) < 0);
+
+        let mut previous = history.quiet_score(&pos, best, [None; 2]);
+        for _ in 0..500 {
+            history.update_quiet_cutoff(&pos, best, &[], [None; 2], 0, 20);
+            let score = history.quiet_score(&pos, best, [None; 2]);
+            assert!(score >= previous && score < MAX_HISTORY);
+            previous = score;
+        }
+        assert!(previous > MAX_HISTORY / 2);
+    }
+}
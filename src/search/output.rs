@@ -1,31 +1,48 @@
 use super::context::SearchStats;
-use crate::{tpt::TranspositionTable, Move};
+use super::score::{Score, ScoreBound};
+use crate::{tpt::TranspositionTable, Move, Position};
 use std::io::Write;
 
+#[expect(
+    clippy::too_many_arguments,
+    reason = "an info line reports every piece of search state the GUI displays"
+)]
 pub(crate) fn print_uci_info(
+    pos: &Position,
     depth: u8,
+    multipv: Option<usize>,
     score: i32,
+    bound: ScoreBound,
     stats: &SearchStats,
     tt: &TranspositionTable,
-    mv: &Move,
+    pv: &[Move],
 ) {
     let elapsed = stats.elapsed_ms();
-    let nps = if elapsed > 0 {
-        (stats.nodes * 1000) / elapsed
-    } else {
-        0
-    };
+    let nodes = stats.total_nodes();
+    let nps = (nodes * 1000).checked_div(elapsed).unwrap_or(0);
+
+    let multipv = multipv.map_or(String::new(), |index| format!(" multipv {}", index));
 
     println!(
-        "info depth {} score cp {} nodes {} time {} nps {} hashfull {} pv {}",
+        "info depth {}{} score {}{} nodes {} time {} nps {} hashfull {} pv {}",
         depth,
-        score,
-        stats.nodes,
+        multipv,
+        Score::from_internal(score),
+        bound.uci_suffix(),
+        nodes,
         elapsed,
         nps,
         tt.hashfull(),
-        mv.to_uci()
+        format_pv(pos, pv)
     );
 
     let _ = std::io::stdout().flush();
 }
+
+#[inline(always)]
+fn format_pv(pos: &Position, pv: &[Move]) -> String {
+    pv.iter()
+        .map(|&mv| pos.move_to_uci(mv))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,63 @@
+use super::params::MAX_DEPTH;
+use crate::Move;
+
+/// Triangular principal-variation table indexed by ply.
+///
+/// Row `ply` holds the best line found from that ply onward. When a move raises
+/// alpha, the child's row at `ply + 1` is copied behind it.
+pub(crate) struct PvTable {
+    moves: [[Move; MAX_DEPTH]; MAX_DEPTH],
+    len: [usize; MAX_DEPTH],
+}
+
+impl PvTable {
+    pub(crate) fn new() -> Self {
+        Self {
+            moves: [[Move(0); MAX_DEPTH]; MAX_DEPTH],
+            len: [0; MAX_DEPTH],
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn clear(&mut self, ply: usize) {
+        if ply < MAX_DEPTH {
+            self.len[ply] = 0;
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn update(&mut self, ply: usize, mv: Move) {
+        if ply >= MAX_DEPTH {
+            return;
+        }
+
+        let child_len = if ply + 1 < MAX_DEPTH {
+            self.len[ply + 1].min(MAX_DEPTH - ply - 1)
+        } else {
+            0
+        };
+
+        let (head, tail) = self.moves.split_at_mut(ply + 1);
+        let row = &mut head[ply];
+        row[0] = mv;
+        if child_len > 0 {
+            row[1..=child_len].copy_from_slice(&tail[0][..child_len]);
+        }
+        self.len[ply] = child_len + 1;
+    }
+
+    #[inline(always)]
+    pub(crate) fn line(&self, ply: usize) -> &[Move] {
+        if ply >= MAX_DEPTH {
+            return &[];
+        }
+
+        &self.moves[ply][..self.len[ply]]
+    }
+}
+
+impl Default for PvTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,21 +1,21 @@
 use crate::eval::EvalProvider;
 use crate::search::context::SearchContext;
 use crate::search::features;
-use crate::search::ordering::{pick_next_move, score_capture, SCORE_PROMOTION};
-use crate::{Move, MoveCollector, Position};
-
-const MAX_MOVES: usize = 256;
+use crate::search::ordering::MovePicker;
+use crate::search::params::MAX_DEPTH;
+use crate::search::score::{refine_eval_with_tt, score_from_tt, score_to_tt};
+use crate::tpt::{EXACT, LOWER_BOUND, UPPER_BOUND};
+use crate::{Move, Position};
 
+/// Capture-only search from `ply` plies below the root.
 pub(crate) fn qsearch<E: EvalProvider>(
     pos: &mut Position,
     ctx: &mut SearchContext<'_, E>,
     mut alpha: i32,
     beta: i32,
-    ply: i32,
+    ply: usize,
 ) -> i32 {
-    ctx.stats.nodes += 1;
-
-    if ctx.stats.should_stop() {
+    if !ctx.stats.enter_node() {
         return alpha;
     }
 
@@ -23,14 +23,63 @@ pub(crate) fn qsearch<E: EvalProvider>(
         return 0;
     }
 
-    if ply >= 64 {
+    if ply >= MAX_DEPTH - 1 {
         return ctx.eval.eval(pos, &mut ctx.eval_state);
     }
 
+    // qsearch tt cutoffs: any stored depth already covers a capture-only search.
+    let hash = pos.hash();
+    let tt_entry = if features::TT_CUTOFFS {
+        ctx.tt.probe(hash).map(|mut entry| {
+            entry.score = score_from_tt(entry.score, ply);
+            entry
+        })
+    } else {
+        None
+    };
+
+    if let Some(entry) = tt_entry {
+        match entry.flag {
+            EXACT => {
+                ctx.stats.tt_hits += 1;
+                return entry.score;
+            }
+            LOWER_BOUND if entry.score >= beta => {
+                ctx.stats.tt_hits += 1;
+                return entry.score;
+            }
+            UPPER_BOUND if entry.score <= alpha => {
+                ctx.stats.tt_hits += 1;
+                return entry.score;
+            }
+            _ => {}
+        }
+    }
+
+    // tt eval: reuse a cached static eval and its score bound when the position was seen before.
+    let raw_eval = match tt_entry.and_then(|entry| entry.static_eval) {
+        Some(eval) => eval,
+        None => ctx.eval.eval(pos, &mut ctx.eval_state),
+    };
+
     // stand pat: test the static evaluation before exploring any forcing captures.
-    let stand_pat = ctx.eval.eval(pos, &mut ctx.eval_state);
+    let corrected_eval = ctx.correction.correct(pos, raw_eval);
+    let stand_pat = tt_entry.map_or(corrected_eval, |entry| {
+        refine_eval_with_tt(corrected_eval, entry.score, entry.flag)
+    });
 
     if stand_pat >= beta {
+        if features::TT_CUTOFFS {
+            ctx.tt.store(
+                hash,
+                Move(0),
+                score_to_tt(beta, ply),
+                Some(raw_eval),
+                0,
+                LOWER_BOUND,
+                false,
+            );
+        }
         return beta;
     }
 
@@ -47,56 +96,18 @@ pub(crate) fn qsearch<E: EvalProvider>(
         }
     }
 
-    let mut collector = MoveCollector::new();
-    pos.generate_captures(&mut collector);
-    let moves = collector.as_slice();
-
-    let mut capture_list = [Move(0); MAX_MOVES];
-    let mut scores = [0i32; MAX_MOVES];
-    let mut capture_count = 0;
-
-    // qsearch move filtering: keep only tactical moves worth searching in the capture phase.
-    for &m in moves {
-        if m.is_capture() || m.is_promotion() {
-            let score = if m.is_capture() {
-                if features::SEE {
-                    let see_score = pos.see(&m);
-                    if see_score < 0 {
-                        continue;
-                    }
-                }
-
-                score_capture(m, pos)
-            } else {
-                SCORE_PROMOTION
-            };
-
-            if m.is_capture() {
-                debug_assert!(score >= 0);
-            }
-
-            capture_list[capture_count] = m;
-            scores[capture_count] = score;
-            capture_count += 1;
-        }
-    }
-
-    if capture_count == 0 {
-        return stand_pat;
-    }
+    // qsearch move ordering: the tt move, then captures and queen promotions that do not lose material.
+    let tt_move = tt_entry.map(|entry| entry.best_move);
+    let mut picker = MovePicker::qsearch(tt_move);
+    let mut best_move = Move(0);
+    let mut searched = 0;
 
     // qsearch move loop: search captures in order until a tactical cutoff appears.
-    for i in 0..capture_count {
+    while let Some(mv) = picker.next(pos, &ctx.history) {
         if ctx.stats.should_stop() {
             break;
         }
-
-        pick_next_move(
-            &mut capture_list[..capture_count],
-            &mut scores[..capture_count],
-            i,
-        );
-        let mv = capture_list[i];
+        searched += 1;
 
         let delta = ctx.eval.update_on_move(&mut ctx.eval_state, pos, mv);
         pos.make_move(mv);
@@ -104,14 +115,68 @@ pub(crate) fn qsearch<E: EvalProvider>(
         pos.unmake_move(mv);
         ctx.eval.update_on_undo(&mut ctx.eval_state, delta);
 
+        if ctx.stats.should_stop() {
+            return alpha;
+        }
+
         if score >= beta {
+            if features::TT_CUTOFFS {
+                ctx.tt.store(
+                    hash,
+                    mv,
+                    score_to_tt(beta, ply),
+                    Some(raw_eval),
+                    0,
+                    LOWER_BOUND,
+                    false,
+                );
+            }
             return beta;
         }
 
         if score > alpha {
             alpha = score;
+            best_move = mv;
         }
     }
 
+    if searched == 0 {
+        if features::TT_CUTOFFS {
+            let flag = if stand_pat > original_alpha {
+                EXACT
+            } else {
+                UPPER_BOUND
+            };
+            ctx.tt.store(
+                hash,
+                Move(0),
+                score_to_tt(stand_pat, ply),
+                Some(raw_eval),
+                0,
+                flag,
+                false,
+            );
+        }
+        return stand_pat;
+    }
+
+    // qsearch tt store: depth-0 entry, exact once a capture or stand pat raised alpha.
+    if features::TT_CUTOFFS {
+        let flag = if alpha > original_alpha {
+            EXACT
+        } else {
+            UPPER_BOUND
+        };
+        ctx.tt.store(
+            hash,
+            best_move,
+            score_to_tt(alpha, ply),
+            Some(raw_eval),
+            0,
+            flag,
+            false,
+        );
+    }
+
     alpha
 }
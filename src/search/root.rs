@@ -1,21 +1,25 @@
-use super::api::SearchInfo;
-use super::context::SearchContext;
+use super::api::{PvLine, SearchInfo};
+use super::context::{SearchContext, SearchStats, ThreadCounters};
 use super::features;
-use super::limits::{should_stop_next_iteration, SearchLimits};
+use super::limits::{should_stop_next_iteration, PonderSignal, SearchLimits};
 use super::node::{search_node, NodeState};
+use super::options::SearchOptions;
 use super::ordering::{pick_next_move, score_move};
 use super::output::print_uci_info;
 use super::params::{ASPIRATION_DEPTH, INFINITY, MAX_MOVES};
-use super::score::{checkmate_score, score_to_tt};
+use super::score::{checkmate_score, score_to_tt, Score, ScoreBound};
+use super::stack::PieceTo;
 use crate::eval::EvalProvider;
-use crate::tpt::{EXACT, LOWER_BOUND, UPPER_BOUND};
+use crate::tpt::{TranspositionTable, EXACT, LOWER_BOUND, UPPER_BOUND};
 use crate::{Move, MoveCollector, Position};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::thread;
 use std::time::Instant;
 
 const INITIAL_ASPIRATION_DELTA: i32 = 25;
 const MAX_ASPIRATION_DELTA: i32 = 1_000;
+const HELPER_STACK_SIZE: usize = 32 * 1024 * 1024;
 
 #[derive(Clone, Copy)]
 struct RootMoveState {
@@ -26,93 +30,284 @@ struct RootMoveState {
     ply: usize,
 }
 
+/// Internal root line kept across iterations in raw search-score space.
+struct RootLine {
+    score: i32,
+    pv: Vec<Move>,
+}
+
+#[expect(
+    clippy::too_many_arguments,
+    reason = "the search driver wires limits and shared signals into every thread"
+)]
 pub(crate) fn run_search<E: EvalProvider>(
     pos: &Position,
     max_depth: u8,
     limits: SearchLimits,
-    tt: &mut crate::tpt::TranspositionTable,
+    options: &SearchOptions,
+    tt: &TranspositionTable,
     stop_signal: Arc<AtomicBool>,
+    ponder: Option<Arc<PonderSignal>>,
     eval: &E,
 ) -> Option<SearchInfo> {
-    let mut pos = pos.clone();
+    let threads = options.threads.max(1);
+    let counters = Arc::new(ThreadCounters::new(threads));
     let start_time = Instant::now();
-    let mut ctx = SearchContext::new(
-        &pos,
-        eval,
-        tt,
-        stop_signal.clone(),
-        limits.hard_time_ms(),
-        start_time,
-    );
+    let root_moves = &collect_root_moves(pos, &options.search_moves);
+
+    // lazy smp: helper threads run their own iterative deepening and only share the tt.
+    let result = thread::scope(|scope| {
+        for thread_id in 1..threads {
+            let stop_signal = stop_signal.clone();
+            let ponder = ponder.clone();
+            let counters = counters.clone();
+            thread::Builder::new()
+                .stack_size(HELPER_STACK_SIZE)
+                .spawn_scoped(scope, move || {
+                    let stats = SearchStats::new(
+                        Some(stop_signal),
+                        start_time,
+                        None,
+                        limits.node_limit(),
+                        ponder,
+                        counters,
+                        thread_id,
+                    );
+                    let mut ctx = SearchContext::new(pos, eval, tt, stats);
+                    run_helper_search(pos, root_moves, max_depth, &mut ctx, thread_id);
+                })
+                .expect("failed to spawn search helper thread");
+        }
 
-    let mut collector = MoveCollector::new();
-    pos.generate_moves(&mut collector);
-    let moves = collector.as_slice();
+        let stats = SearchStats::new(
+            Some(stop_signal.clone()),
+            start_time,
+            limits.hard_time_ms(),
+            limits.node_limit(),
+            ponder,
+            counters.clone(),
+            0,
+        );
+        let mut ctx = SearchContext::new(pos, eval, tt, stats);
+        let result = run_main_search(pos, root_moves, max_depth, limits, options, &mut ctx);
+
+        stop_signal.store(true, Ordering::Relaxed);
+        result
+    });
+
+    result.map(|info| SearchInfo {
+        nodes: counters.total_nodes(),
+        tt_hits: counters.total_tt_hits(),
+        ..info
+    })
+}
 
-    if moves.is_empty() {
+fn run_main_search<E: EvalProvider>(
+    pos: &Position,
+    root_moves: &[Move],
+    max_depth: u8,
+    limits: SearchLimits,
+    options: &SearchOptions,
+    ctx: &mut SearchContext<'_, E>,
+) -> Option<SearchInfo> {
+    let mut pos = pos.clone();
+
+    if root_moves.is_empty() {
         return None;
     }
 
-    let mut best_move = Some(moves[0]);
-    let mut best_score = 0;
+    let multi_pv = options.multi_pv.clamp(1, root_moves.len());
+    let mut lines = vec![RootLine {
+        score: 0,
+        pv: vec![root_moves[0]],
+    }];
     let mut completed_depth = 0;
 
     // iterative deepening: search depth 1..N and keep the best fully completed result.
-    for depth in 1..=max_depth {
+    'deepening: for depth in 1..=max_depth {
         let depth_start = Instant::now();
 
         if ctx.stats.should_stop() {
             break;
         }
 
-        let (iteration_best_score, iteration_best_move) =
-            search_with_aspiration(&mut pos, &mut ctx, depth, best_score);
+        // multipv: search each line with its own window, excluding moves already ranked above it.
+        let mut iteration_lines = Vec::with_capacity(multi_pv);
+        let mut excluded = Vec::with_capacity(multi_pv);
 
-        if ctx.stats.should_stop() {
-            break;
+        for pv_index in 0..multi_pv {
+            let prev_score = lines
+                .get(pv_index)
+                .or(lines.last())
+                .map_or(0, |line| line.score);
+            let label = multipv_label(multi_pv, pv_index);
+
+            let (score, mv) = search_with_aspiration(
+                &mut pos, ctx, root_moves, depth, prev_score, &excluded, label,
+            );
+
+            if ctx.stats.should_stop() {
+                break 'deepening;
+            }
+
+            excluded.push(mv);
+            iteration_lines.push(RootLine {
+                score,
+                pv: root_pv(ctx, mv),
+            });
         }
 
-        best_move = Some(iteration_best_move);
-        best_score = iteration_best_score;
+        iteration_lines.sort_by_key(|line| std::cmp::Reverse(line.score));
+        lines = iteration_lines;
         completed_depth = depth;
 
-        print_uci_info(depth, best_score, &ctx.stats, ctx.tt, &iteration_best_move);
+        for (pv_index, line) in lines.iter().enumerate() {
+            print_uci_info(
+                &pos,
+                depth,
+                multipv_label(multi_pv, pv_index),
+                line.score,
+                ScoreBound::Exact,
+                &ctx.stats,
+                ctx.tt,
+                &line.pv,
+            );
+        }
+
+        // ponder: no clock applies until ponderhit, after which limits count from the hit.
+        let Some(clock_start) = ctx.stats.clock_start() else {
+            continue;
+        };
+
+        // mate search: stop once a forced mate within the requested number of moves is proven.
+        if let Some(mate_moves) = limits.mate_limit() {
+            if let Score::Mate(moves) = Score::from_internal(lines[0].score) {
+                if moves > 0 && moves as u32 <= mate_moves {
+                    ctx.stats.signal_stop();
+                    break;
+                }
+            }
+        }
 
         let current_depth_time = depth_start.elapsed().as_millis() as u64;
-        if should_stop_next_iteration(limits, start_time, current_depth_time) {
-            stop_signal.store(true, Ordering::Relaxed);
+        if should_stop_next_iteration(limits, clock_start, current_depth_time) {
+            ctx.stats.signal_stop();
             break;
         }
 
         if let Some(max_time) = limits.hard_time_ms() {
-            if start_time.elapsed().as_millis() as u64 >= max_time {
-                stop_signal.store(true, Ordering::Relaxed);
+            if clock_start.elapsed().as_millis() as u64 >= max_time {
+                ctx.stats.signal_stop();
                 break;
             }
         }
     }
 
-    best_move.map(|mv| SearchInfo {
-        best_move: mv,
-        score: best_score,
+    ctx.stats.publish();
+
+    let best = &lines[0];
+    Some(SearchInfo {
+        best_move: best.pv[0],
+        score: Score::from_internal(best.score),
         depth: completed_depth,
         nodes: ctx.stats.nodes,
         time_ms: ctx.stats.elapsed_ms(),
         tt_hits: ctx.stats.tt_hits,
+        pv: best.pv.clone(),
+        lines: lines
+            .iter()
+            .map(|line| PvLine {
+                score: Score::from_internal(line.score),
+                pv: line.pv.clone(),
+            })
+            .collect(),
     })
 }
 
+/// Lazy SMP helper: searches the same root without reporting, staggering depths
+/// by thread so helpers fill the shared table with different parts of the tree.
+fn run_helper_search<E: EvalProvider>(
+    pos: &Position,
+    root_moves: &[Move],
+    max_depth: u8,
+    ctx: &mut SearchContext<'_, E>,
+    thread_id: usize,
+) {
+    let mut pos = pos.clone();
+    let mut prev_score = 0;
+    let start_depth = 1 + (thread_id % 2) as u8;
+
+    for depth in start_depth..=max_depth {
+        if ctx.stats.should_stop() {
+            break;
+        }
+
+        let (score, _) =
+            search_with_aspiration(&mut pos, ctx, root_moves, depth, prev_score, &[], None);
+
+        if ctx.stats.should_stop() {
+            break;
+        }
+
+        prev_score = score;
+    }
+
+    ctx.stats.publish();
+}
+
+/// Legal root moves, narrowed to `search_moves` when that list names any of them.
+fn collect_root_moves(pos: &Position, search_moves: &[Move]) -> Vec<Move> {
+    let mut collector = MoveCollector::new();
+    pos.generate_moves(&mut collector);
+    let legal = collector.as_slice();
+
+    // searchmoves: an empty or entirely illegal restriction falls back to every legal move.
+    let restricted: Vec<Move> = legal
+        .iter()
+        .copied()
+        .filter(|mv| search_moves.contains(mv))
+        .collect();
+
+    if restricted.is_empty() {
+        legal.to_vec()
+    } else {
+        restricted
+    }
+}
+
+/// Returns the 1-based `multipv` index to print, or `None` in single-PV mode.
+#[inline(always)]
+const fn multipv_label(multi_pv: usize, pv_index: usize) -> Option<usize> {
+    if multi_pv > 1 {
+        Some(pv_index + 1)
+    } else {
+        None
+    }
+}
+
+/// Returns the root principal variation, falling back to the bare best move when
+/// the table holds no line for it (for example after a fail-low first move).
+#[inline(always)]
+fn root_pv<E: EvalProvider>(ctx: &SearchContext<'_, E>, best_move: Move) -> Vec<Move> {
+    let line = ctx.pv.line(0);
+    if line.first().is_some_and(|mv| mv.0 == best_move.0) {
+        line.to_vec()
+    } else {
+        vec![best_move]
+    }
+}
+
 #[inline(always)]
 fn search_with_aspiration<E: EvalProvider>(
     pos: &mut Position,
     ctx: &mut SearchContext<'_, E>,
+    root_moves: &[Move],
     depth: u8,
     prev_score: i32,
+    excluded: &[Move],
+    multipv: Option<usize>,
 ) -> (i32, Move) {
-    let mut collector = MoveCollector::new();
-    pos.generate_moves(&mut collector);
-
-    if collector.as_slice().is_empty() {
+    if root_moves.is_empty() {
         return if pos.is_in_check() {
             (checkmate_score(0), Move(0))
         } else {
@@ -121,13 +316,27 @@ fn search_with_aspiration<E: EvalProvider>(
     }
 
     let mut moves = [Move(0); MAX_MOVES];
-    let count = collector.len();
-    moves[..count].copy_from_slice(&collector.as_slice()[..count]);
+    let mut count = 0;
+    for &mv in root_moves {
+        if !excluded.contains(&mv) {
+            moves[count] = mv;
+            count += 1;
+        }
+    }
     let moves_slice = &mut moves[..count];
+    let first_line = excluded.is_empty();
 
     // aspiration windows: search around the previous iteration score before widening.
     if !features::ASPIRATION_WINDOWS || depth < ASPIRATION_DEPTH {
-        return search_root(pos, ctx, moves_slice, depth, -INFINITY, INFINITY);
+        return search_root(
+            pos,
+            ctx,
+            moves_slice,
+            depth,
+            -INFINITY,
+            INFINITY,
+            first_line,
+        );
     }
 
     let mut delta = INITIAL_ASPIRATION_DELTA;
@@ -135,7 +344,7 @@ fn search_with_aspiration<E: EvalProvider>(
     let mut beta = prev_score + delta;
 
     loop {
-        let (score, best_move) = search_root(pos, ctx, moves_slice, depth, alpha, beta);
+        let (score, best_move) = search_root(pos, ctx, moves_slice, depth, alpha, beta, first_line);
 
         if ctx.stats.should_stop() {
             return (score, best_move);
@@ -145,6 +354,25 @@ fn search_with_aspiration<E: EvalProvider>(
             return (score, best_move);
         }
 
+        // aspiration bounds: report the failed window before widening and re-searching.
+        if ctx.stats.is_main_thread() {
+            let bound = if score <= alpha {
+                ScoreBound::Upper
+            } else {
+                ScoreBound::Lower
+            };
+            print_uci_info(
+                pos,
+                depth,
+                multipv,
+                score,
+                bound,
+                &ctx.stats,
+                ctx.tt,
+                &root_pv(ctx, best_move),
+            );
+        }
+
         if score <= alpha {
             beta = (alpha + beta) / 2;
             alpha = alpha.saturating_sub(delta);
@@ -170,9 +398,18 @@ pub(crate) fn search_root<E: EvalProvider>(
     depth: u8,
     mut alpha: i32,
     beta: i32,
+    store_tt: bool,
 ) -> (i32, Move) {
     let in_check = pos.is_in_check();
     let alpha_start = alpha;
+    ctx.pv.clear(0);
+
+    // search stack: ply-2 nodes compare their eval with the root's to decide whether they improve.
+    let root_eval = (!in_check).then(|| {
+        let raw_eval = ctx.eval.eval(pos, &mut ctx.eval_state);
+        ctx.correction.correct(pos, raw_eval)
+    });
+    ctx.stack.set_static_eval(0, root_eval);
 
     // tt move ordering: seed root move ordering from the transposition table.
     let tt_move = if features::TT_MOVE_ORDERING {
@@ -186,7 +423,7 @@ pub(crate) fn search_root<E: EvalProvider>(
 
     // root move ordering: score all root moves before iterative pick-next selection.
     for i in 0..move_count {
-        scores[i] = score_move(moves[i], pos, tt_move, Some(&ctx.history), 0);
+        scores[i] = score_move(moves[i], pos, tt_move, Some(&ctx.history), 0, [None; 2]);
     }
 
     let mut best_score = -INFINITY;
@@ -201,6 +438,7 @@ pub(crate) fn search_root<E: EvalProvider>(
         pick_next_move(moves, &mut scores, i);
         let mv = moves[i];
 
+        ctx.stack.set_move(0, Some(PieceTo::new(pos, mv)), 0);
         let delta = ctx.eval.update_on_move(&mut ctx.eval_state, pos, mv);
         pos.make_move(mv);
         let gives_check = pos.is_in_check();
@@ -237,6 +475,7 @@ pub(crate) fn search_root<E: EvalProvider>(
             best_move = mv;
             if score > alpha {
                 alpha = score;
+                ctx.pv.update(0, mv);
                 if score >= beta {
                     break;
                 }
@@ -245,6 +484,7 @@ pub(crate) fn search_root<E: EvalProvider>(
     }
 
     // tt store: keep the root result so later iterations can reuse the score and move.
+    // secondary multipv lines are skipped since they exclude the real best move.
     let flag = if best_score >= beta {
         LOWER_BOUND
     } else if best_score <= alpha_start {
@@ -253,13 +493,15 @@ pub(crate) fn search_root<E: EvalProvider>(
         EXACT
     };
 
-    if features::TT_CUTOFFS {
+    if features::TT_CUTOFFS && store_tt {
         ctx.tt.store(
             pos.hash(),
             best_move,
             score_to_tt(best_score, 0),
+            None,
             depth,
             flag,
+            true,
         );
     }
 
@@ -290,6 +532,7 @@ fn search_root_child<E: EvalProvider>(
                 depth,
                 state.move_num,
                 state.pv_node,
+                true,
                 mv,
             );
             let reduced_depth = depth.saturating_sub(1 + reduction);
@@ -335,8 +578,14 @@ fn search_root_child<E: EvalProvider>(
     );
 
     let mut score = if do_lmr {
-        let reduction =
-            super::heuristics::calculate_lmr_reduction(depth, state.move_num, state.pv_node, mv);
+        // the root has no earlier eval to fall behind, so it always counts as improving.
+        let reduction = super::heuristics::calculate_lmr_reduction(
+            depth,
+            state.move_num,
+            state.pv_node,
+            true,
+            mv,
+        );
         let reduced_depth = depth.saturating_sub(1 + reduction);
 
         -search_node(
@@ -1,4 +1,6 @@
 use super::params::{MATE_VALUE, MAX_DEPTH};
+use crate::tpt::{EXACT, LOWER_BOUND, UPPER_BOUND};
+use std::fmt;
 
 /// Scores above this magnitude are mate scores, not centipawn evals.
 const TT_MATE_THRESHOLD: i32 = MATE_VALUE - MAX_DEPTH as i32;
@@ -38,3 +40,81 @@ pub(crate) const fn score_from_tt(score: i32, ply: usize) -> i32 {
         score
     }
 }
+
+/// Tighten a static eval with a stored TT bound on the search score.
+/// An exact score replaces the eval outright; a lower bound above it or an upper
+/// bound below it is a better estimate than the raw eval. Mate scores are ignored.
+#[inline(always)]
+pub(crate) const fn refine_eval_with_tt(static_eval: i32, tt_score: i32, flag: u8) -> i32 {
+    if tt_score >= TT_MATE_THRESHOLD || tt_score <= -TT_MATE_THRESHOLD {
+        return static_eval;
+    }
+
+    match flag {
+        EXACT => tt_score,
+        LOWER_BOUND if tt_score > static_eval => tt_score,
+        UPPER_BOUND if tt_score < static_eval => tt_score,
+        _ => static_eval,
+    }
+}
+
+/// Search score in reporting form.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Score {
+    /// Centipawn evaluation from the side to move's perspective.
+    Cp(i32),
+    /// Forced mate in the given number of moves; negative when the side to move is mated.
+    Mate(i32),
+}
+
+impl Score {
+    /// Converts an internal search score into centipawns or a mate distance in moves.
+    #[inline(always)]
+    pub const fn from_internal(score: i32) -> Self {
+        if score >= TT_MATE_THRESHOLD {
+            Self::Mate((MATE_VALUE - score + 1) / 2)
+        } else if score <= -TT_MATE_THRESHOLD {
+            Self::Mate(-(MATE_VALUE + score) / 2)
+        } else {
+            Self::Cp(score)
+        }
+    }
+
+    /// Returns `true` when the score is a forced mate for either side.
+    #[inline(always)]
+    pub const fn is_mate(self) -> bool {
+        matches!(self, Self::Mate(_))
+    }
+}
+
+/// Formats the score as the UCI `score` payload, e.g. `cp 31` or `mate -2`.
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cp(cp) => write!(f, "cp {}", cp),
+            Self::Mate(moves) => write!(f, "mate {}", moves),
+        }
+    }
+}
+
+/// Whether a reported score is exact or only a bound from a failed aspiration window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScoreBound {
+    Exact,
+    /// The search failed high; the true score is at least the reported one.
+    Lower,
+    /// The search failed low; the true score is at most the reported one.
+    Upper,
+}
+
+impl ScoreBound {
+    /// Returns the UCI suffix for this bound, including its leading space.
+    #[inline(always)]
+    pub const fn uci_suffix(self) -> &'static str {
+        match self {
+            Self::Exact => "",
+            Self::Lower => " lowerbound",
+            Self::Upper => " upperbound",
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use super::params::MAX_DEPTH;
+use crate::{types::Color, Move, Piece, Position};
+
+/// Moving piece and destination of a move, the key continuation tables index by.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct PieceTo {
+    pub(crate) piece: Piece,
+    pub(crate) color: Color,
+    pub(crate) to: u8,
+}
+
+impl PieceTo {
+    /// Describes `mv` in `pos` before it is played; promotions count as pawn moves.
+    #[inline(always)]
+    pub(crate) fn new(pos: &Position, mv: Move) -> Self {
+        let (piece, color) = pos
+            .piece_at(mv.from())
+            .expect("move must start on an occupied square");
+
+        Self {
+            piece,
+            color,
+            to: mv.to() as u8,
+        }
+    }
+
+    /// Index of the piece, colour included, and destination in `0..12 * 64`.
+    #[inline(always)]
+    pub(crate) const fn index(self) -> usize {
+        (self.color as usize * 6 + self.piece as usize) * 64 + self.to as usize
+    }
+}
+
+/// The moves played one and two plies before a node, most recent first.
+///
+/// `None` marks a null move, or a ply above the search root.
+pub(crate) type Continuation = [Option<PieceTo>; 2];
+
+/// What the search stack remembers about one ply of the current path.
+#[derive(Clone, Copy)]
+struct StackEntry {
+    /// Move made from this ply; `None` for a null move.
+    moved: Option<PieceTo>,
+    /// Static eval of the node at this ply; `None` when it was in check.
+    static_eval: Option<i32>,
+    /// Extensions granted on the path up to and including the move made from this ply.
+    extensions: u8,
+}
+
+impl StackEntry {
+    const EMPTY: Self = Self {
+        moved: None,
+        static_eval: None,
+        extensions: 0,
+    };
+}
+
+/// Per-ply record of the current search path.
+///
+/// Entry `ply` describes the node at `ply` and the move made from it, so a node
+/// reads its predecessors from the entries below its own.
+pub(crate) struct SearchStack {
+    entries: [StackEntry; MAX_DEPTH],
+}
+
+impl SearchStack {
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: [StackEntry::EMPTY; MAX_DEPTH],
+        }
+    }
+
+    #[inline(always)]
+    fn back(&self, ply: usize, back: usize) -> Option<&StackEntry> {
+        ply.checked_sub(back).and_then(|idx| self.entries.get(idx))
+    }
+
+    /// Records the move about to be made from `ply`, `None` for a null move, and
+    /// the depth `extension` it is searched with.
+    #[inline(always)]
+    pub(crate) fn set_move(&mut self, ply: usize, moved: Option<PieceTo>, extension: u8) {
+        let extensions = self.extensions(ply).saturating_add(extension);
+        if let Some(entry) = self.entries.get_mut(ply) {
+            entry.moved = moved;
+            entry.extensions = extensions;
+        }
+    }
+
+    /// Returns the moves that led to the node at `ply`.
+    #[inline(always)]
+    pub(crate) fn continuation(&self, ply: usize) -> Continuation {
+        let at = |back: usize| self.back(ply, back).and_then(|entry| entry.moved);
+
+        [at(1), at(2)]
+    }
+
+    /// Returns the extensions granted on the path from the root to the node at `ply`.
+    #[inline(always)]
+    pub(crate) fn extensions(&self, ply: usize) -> u8 {
+        self.back(ply, 1).map_or(0, |entry| entry.extensions)
+    }
+
+    /// Records the static eval of the node at `ply`; `None` when it is in check.
+    #[inline(always)]
+    pub(crate) fn set_static_eval(&mut self, ply: usize, static_eval: Option<i32>) {
+        if let Some(entry) = self.entries.get_mut(ply) {
+            entry.static_eval = static_eval;
+        }
+    }
+
+    /// Returns `true` if `static_eval` at `ply` is better than the last known eval
+    /// for the same side, two plies back or failing that four.
+    ///
+    /// A node in check is never improving; with no earlier eval to compare
+    /// against, every other node is.
+    #[inline(always)]
+    pub(crate) fn is_improving(&self, ply: usize, static_eval: Option<i32>) -> bool {
+        let Some(static_eval) = static_eval else {
+            return false;
+        };
+        let earlier = |back: usize| self.back(ply, back).and_then(|entry| entry.static_eval);
+
+        earlier(2)
+            .or_else(|| earlier(4))
+            .is_none_or(|earlier| static_eval > earlier)
+    }
+}
+
+impl Default for SearchStack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SearchStack;
+
+    #[test]
+    fn improving_compares_with_the_same_side_two_plies_back() {
+        let mut stack = SearchStack::new();
+
+        // no earlier eval for this side yet.
+        stack.set_static_eval(0, Some(10));
+        assert!(stack.is_improving(1, Some(-50)));
+
+        assert!(stack.is_improving(2, Some(20)));
+        assert!(!stack.is_improving(2, Some(10)));
+        assert!(!stack.is_improving(2, None));
+
+        // a node in check two plies back falls through to the eval four plies back.
+        stack.set_static_eval(2, None);
+        assert!(!stack.is_improving(4, Some(5)));
+        assert!(stack.is_improving(4, Some(15)));
+    }
+
+    #[test]
+    fn extensions_accumulate_along_the_path() {
+        let mut stack = SearchStack::new();
+
+        stack.set_move(0, None, 1);
+        stack.set_move(1, None, 0);
+        stack.set_move(2, None, 1);
+        assert_eq!(stack.extensions(0), 0);
+        assert_eq!(stack.extensions(2), 1);
+        assert_eq!(stack.extensions(3), 2);
+
+        // a sibling replaces the move at ply 2 without disturbing the path above it.
+        stack.set_move(2, None, 0);
+        assert_eq!(stack.extensions(3), 1);
+    }
+}
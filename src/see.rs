@@ -215,7 +215,7 @@ mod test_see {
     }
     #[test]
     fn test_stand_pat() {
-        let fen = "b3r3/7q/3n4/8/4P3/3Q4/5N2/4R2B b - - 0 1";
+        let fen = "b3r2k/7q/3n4/8/4P3/3Q4/5N2/K3R2B b - - 0 1";
         let pos = Position::from_fen(fen).expect("Invalid FEN");
 
         let m = Move::new("d6".idx(), "e4".idx(), MoveType::Capture);
@@ -1,9 +1,19 @@
 //! Transposition table storage.
 //!
-//! The table stores one packed entry per bucket and uses a simple depth-and-age
-//! replacement policy tuned for single-search throughput.
+//! The table is an array of cache-line sized clusters holding four packed
+//! entries each. A probe scans one cluster, so a single memory fetch covers every
+//! candidate slot. Stores reuse the slot already holding the position or an empty
+//! slot, and otherwise evict the entry with the lowest replacement value, which
+//! favours deep, recent and exact entries.
+//!
+//! Every entry is two atomic 64-bit words: the packed payload and a signature
+//! equal to `hash ^ payload`. Readers and writers never lock; a probe that races
+//! with a store may observe one new word and one stale word, in which case the
+//! signature no longer matches the hash and the probe is treated as a miss. This
+//! lets the table live behind an `Arc` shared by search threads and the UCI loop.
 
 use crate::Move;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
 
 /// Decoded transposition-table entry returned by probes.
 #[derive(Copy, Clone)]
@@ -14,10 +24,14 @@ pub struct TTEntry {
     pub best_move: Move,
     /// Stored score after TT normalization.
     pub score: i32,
+    /// Static evaluation of the position, if one was computed when storing.
+    pub static_eval: Option<i32>,
     /// Search depth associated with the entry.
     pub depth: u8,
     /// Bound type for the stored score.
     pub flag: u8,
+    /// Whether the position was searched as part of a principal variation.
+    pub is_pv: bool,
     /// Table generation used for aging decisions.
     pub age: u8,
 }
@@ -28,30 +42,103 @@ impl Default for TTEntry {
             key: 0,
             best_move: Move(0),
             score: 0,
+            static_eval: None,
             depth: 0,
             flag: 0,
+            is_pv: false,
             age: 0,
         }
     }
 }
 
-#[derive(Clone, Copy, Default)]
+#[derive(Default)]
 struct PackedTTEntry {
-    data: u64,
-    signature: u64,
+    data: AtomicU64,
+    signature: AtomicU64,
 }
 
-/// Packed transposition table with one entry per bucket.
+impl PackedTTEntry {
+    #[inline(always)]
+    fn load(&self) -> (u64, u64) {
+        (
+            self.data.load(Ordering::Relaxed),
+            self.signature.load(Ordering::Relaxed),
+        )
+    }
+
+    #[inline(always)]
+    fn write(&self, hash: u64, data: u64) {
+        self.data.store(data, Ordering::Relaxed);
+        self.signature.store(hash ^ data, Ordering::Relaxed);
+    }
+}
+
+/// Four entries sharing one 64-byte cache line.
+#[derive(Default)]
+#[repr(C, align(64))]
+struct TTCluster {
+    entries: [PackedTTEntry; CLUSTER_ENTRIES],
+}
+
+/// Packed, lock-free transposition table of multi-entry clusters.
 pub struct TranspositionTable {
-    table: Vec<PackedTTEntry>,
+    table: Box<[TTCluster]>,
     mask: u64,
-    generation: u8,
+    generation: AtomicU8,
+}
+
+const CLUSTER_ENTRIES: usize = 4;
+const CLUSTER_SIZE_BYTES: usize = std::mem::size_of::<TTCluster>();
+const AGE_MASK: u8 = 0x0F;
+/// Depth-equivalent cost of each generation an entry has aged by.
+const AGE_WEIGHT: i32 = 4;
+/// Depth-equivalent bonus for keeping an exact entry over a bound.
+const EXACT_BONUS: i32 = 2;
+
+// data word layout, low to high:
+// move 16 | score 17 (signed) | static eval 16 (signed) | depth 8 | flag 2 | pv 1 | age 4
+const SCORE_SHIFT: u64 = 16;
+const SCORE_BITS: u32 = 17;
+const EVAL_SHIFT: u64 = 33;
+const DEPTH_SHIFT: u64 = 49;
+const FLAG_SHIFT: u64 = 57;
+const PV_SHIFT: u64 = 59;
+const AGE_SHIFT: u64 = 60;
+/// Depth by which a same-position store may undercut the stored entry and still replace it.
+const SAME_KEY_DEPTH_MARGIN: u32 = 3;
+/// Marks an entry stored without a static evaluation.
+const EVAL_NONE: i16 = i16::MIN;
+
+#[inline(always)]
+fn unpack_move(data: u64) -> Move {
+    Move((data & 0xFFFF) as u16)
+}
+
+#[inline(always)]
+fn unpack_score(data: u64) -> i32 {
+    let raw = ((data >> SCORE_SHIFT) as u32) << (32 - SCORE_BITS);
+    (raw as i32) >> (32 - SCORE_BITS)
+}
+
+#[inline(always)]
+fn unpack_eval(data: u64) -> i16 {
+    (data >> EVAL_SHIFT) as u16 as i16
 }
 
-const ENTRY_SIZE_BYTES: usize = 24;
-const FLAG_SHIFT: u64 = 56;
-const AGE_SHIFT: u64 = 58;
-const DEPTH_SHIFT: u64 = 48;
+#[inline(always)]
+fn unpack_depth(data: u64) -> u8 {
+    (data >> DEPTH_SHIFT) as u8
+}
+
+#[inline(always)]
+fn unpack_flag(data: u64) -> u8 {
+    ((data >> FLAG_SHIFT) & 0x3) as u8
+}
+
+#[inline(always)]
+fn unpack_age(data: u64) -> u8 {
+    ((data >> AGE_SHIFT) as u8) & AGE_MASK
+}
 
 #[inline(always)]
 fn unpack_entry(hash: u64, data: u64, signature: u64) -> Option<TTEntry> {
@@ -59,22 +146,48 @@ fn unpack_entry(hash: u64, data: u64, signature: u64) -> Option<TTEntry> {
         return None;
     }
 
+    let eval = unpack_eval(data);
+
     Some(TTEntry {
         key: hash,
-        best_move: Move(((data >> 32) & 0xFFFF) as u16),
-        score: (data as u32) as i32,
-        depth: ((data >> DEPTH_SHIFT) & 0xFF) as u8,
-        flag: ((data >> FLAG_SHIFT) & 0x3) as u8,
-        age: ((data >> AGE_SHIFT) & 0x3F) as u8,
+        best_move: unpack_move(data),
+        score: unpack_score(data),
+        static_eval: (eval != EVAL_NONE).then_some(eval as i32),
+        depth: unpack_depth(data),
+        flag: unpack_flag(data),
+        is_pv: (data >> PV_SHIFT) & 1 != 0,
+        age: unpack_age(data),
     })
 }
 
+/// Higher values are more worth keeping when a cluster is full.
 #[inline(always)]
-fn pack_entry(best_move: Move, score: i32, depth: u8, flag: u8, age: u8) -> u64 {
-    (score as u32) as u64
-        | ((best_move.0 as u64) << 32)
+fn replacement_value(data: u64, generation: u8) -> i32 {
+    let relative_age = generation.wrapping_sub(unpack_age(data)) & AGE_MASK;
+    let exact = unpack_flag(data) == EXACT;
+
+    unpack_depth(data) as i32 - AGE_WEIGHT * relative_age as i32
+        + if exact { EXACT_BONUS } else { 0 }
+}
+
+#[inline(always)]
+fn pack_entry(
+    best_move: Move,
+    score: i32,
+    static_eval: i16,
+    depth: u8,
+    flag: u8,
+    is_pv: bool,
+    age: u8,
+) -> u64 {
+    let score_mask = (1u64 << SCORE_BITS) - 1;
+
+    best_move.0 as u64
+        | (((score as u32) as u64 & score_mask) << SCORE_SHIFT)
+        | ((static_eval as u16 as u64) << EVAL_SHIFT)
         | ((depth as u64) << DEPTH_SHIFT)
         | ((flag as u64) << FLAG_SHIFT)
+        | ((is_pv as u64) << PV_SHIFT)
         | ((age as u64) << AGE_SHIFT)
 }
 
@@ -82,117 +195,303 @@ impl TranspositionTable {
     /// Allocates a transposition table sized in megabytes.
     pub fn new_mb(mb: usize) -> Self {
         let bytes = mb * 1024 * 1024;
-        let entries = bytes / ENTRY_SIZE_BYTES;
+        let clusters = bytes / CLUSTER_SIZE_BYTES;
 
-        let size = if entries.is_power_of_two() {
-            entries
+        let size = if clusters.is_power_of_two() {
+            clusters
         } else {
-            entries.next_power_of_two() / 2
-        };
-
-        let mut table = Vec::with_capacity(size);
-        for _ in 0..size {
-            table.push(PackedTTEntry::default());
+            clusters.next_power_of_two() / 2
         }
+        .max(1);
 
         TranspositionTable {
-            table,
+            table: (0..size).map(|_| TTCluster::default()).collect(),
             mask: (size - 1) as u64,
-            generation: 0,
+            generation: AtomicU8::new(0),
         }
     }
 
+    #[inline(always)]
+    fn cluster(&self, hash: u64) -> &TTCluster {
+        // the mask is `len - 1` for a power-of-two length, so the index is always in bounds.
+        &self.table[(hash & self.mask) as usize]
+    }
+
+    #[inline(always)]
+    fn generation(&self) -> u8 {
+        self.generation.load(Ordering::Relaxed) & AGE_MASK
+    }
+
     /// Starts a new search generation for aging decisions.
     #[inline(always)]
-    pub fn new_search(&mut self) {
-        self.generation = self.generation.wrapping_add(1);
+    pub fn new_search(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Looks up a position by hash.
     #[inline(always)]
     pub fn probe(&self, hash: u64) -> Option<TTEntry> {
-        let idx = (hash & self.mask) as usize;
+        let cluster = self.cluster(hash);
 
         #[cfg(target_arch = "x86_64")]
         unsafe {
             use std::arch::x86_64::_mm_prefetch;
-            let ptr = self.table.as_ptr().add(idx) as *const i8;
+            let ptr = cluster as *const TTCluster as *const i8;
             _mm_prefetch::<3>(ptr);
         }
 
-        let entry = unsafe { self.table.get_unchecked(idx) };
-        let data = entry.data;
-        let signature = entry.signature;
-
-        unpack_entry(hash, data, signature)
+        cluster.entries.iter().find_map(|entry| {
+            let (data, signature) = entry.load();
+            unpack_entry(hash, data, signature)
+        })
     }
 
-    /// Stores a search result for `hash` if the replacement policy allows it.
+    /// Stores a search result for `hash`, choosing a slot within its cluster.
+    ///
+    /// An entry for the same position is overwritten, keeping its move and static
+    /// evaluation if the new result has none and always keeping its pv mark. The
+    /// store is skipped instead when that entry is from the current generation and
+    /// `depth + 3` is still below its depth. Otherwise an empty slot is used, and
+    /// failing that the slot with the lowest [`replacement_value`] is evicted.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "entries are packed from their raw fields on the hot path"
+    )]
     #[inline(always)]
-    pub fn store(&mut self, hash: u64, best_move: Move, score: i32, depth: u8, flag: u8) {
-        let idx = (hash & self.mask) as usize;
-        let entry = unsafe { self.table.get_unchecked(idx) };
+    pub fn store(
+        &self,
+        hash: u64,
+        best_move: Move,
+        score: i32,
+        static_eval: Option<i32>,
+        depth: u8,
+        flag: u8,
+        is_pv: bool,
+    ) {
+        let generation = self.generation();
+        let entries = &self.cluster(hash).entries;
 
-        let old_data = entry.data;
-        let old_signature = entry.signature;
-        let old_hash = old_data ^ old_signature;
+        let mut victim = &entries[0];
+        let mut victim_data = None;
+        let mut victim_value = i32::MAX;
 
-        let mut replace = false;
-        let generation = self.generation;
+        for entry in entries {
+            let (data, signature) = entry.load();
+            let entry_hash = data ^ signature;
 
-        if old_hash == 0 || old_hash == hash {
-            replace = true;
-        } else {
-            let old_age = ((old_data >> AGE_SHIFT) & 0x3F) as u8;
-            let current_age_bits = generation & 0x3F;
+            if entry_hash == hash || entry_hash == 0 {
+                victim = entry;
+                victim_data = (entry_hash == hash).then_some(data);
+                break;
+            }
 
-            if old_age != current_age_bits && depth >= ((old_data >> DEPTH_SHIFT) & 0xFF) as u8 {
-                replace = true;
+            let value = replacement_value(data, generation);
+            if value < victim_value {
+                victim = entry;
+                victim_value = value;
             }
         }
 
-        if replace {
-            let new_data = pack_entry(best_move, score, depth, flag, generation & 0x3F);
-            let new_signature = hash ^ new_data;
-
-            unsafe {
-                let slot = self.table.get_unchecked_mut(idx);
-                slot.data = new_data;
-                slot.signature = new_signature;
+        // shallow results such as qsearch entries must not wipe out deep entries for the same
+        // position, whatever their bound.
+        if let Some(old_data) = victim_data {
+            if unpack_age(old_data) == generation
+                && depth as u32 + SAME_KEY_DEPTH_MARGIN < unpack_depth(old_data) as u32
+            {
+                return;
             }
         }
+
+        // a position once searched on the pv stays marked, even when a non-pv search refreshes it.
+        let is_pv = is_pv || victim_data.is_some_and(|old_data| (old_data >> PV_SHIFT) & 1 != 0);
+
+        let best_move = match victim_data {
+            Some(old_data) if best_move.0 == 0 => unpack_move(old_data),
+            _ => best_move,
+        };
+
+        // evals are clamped just inside i16 so they never collide with the missing-eval marker.
+        let static_eval = match (static_eval, victim_data) {
+            (Some(eval), _) => eval.clamp(-(i16::MAX as i32), i16::MAX as i32) as i16,
+            (None, Some(old_data)) => unpack_eval(old_data),
+            (None, None) => EVAL_NONE,
+        };
+
+        victim.write(
+            hash,
+            pack_entry(
+                best_move,
+                score,
+                static_eval,
+                depth,
+                flag,
+                is_pv,
+                generation,
+            ),
+        );
     }
 
     /// Clears all entries and resets the generation counter.
-    pub fn clear(&mut self) {
-        for entry in &mut self.table {
-            entry.data = 0;
-            entry.signature = 0;
+    ///
+    /// Safe to call while other threads hold the table, though concurrent
+    /// searches will simply start refilling it.
+    pub fn clear(&self) {
+        for entry in self.table.iter().flat_map(|cluster| &cluster.entries) {
+            entry.data.store(0, Ordering::Relaxed);
+            entry.signature.store(0, Ordering::Relaxed);
         }
-        self.generation = 0;
+        self.generation.store(0, Ordering::Relaxed);
     }
 
     /// Returns hash table occupancy in permille, matching the UCI `hashfull` convention.
+    ///
+    /// Only entries written during the current search generation are counted, so
+    /// stale entries from earlier searches do not make the table look full.
     pub fn hashfull(&self) -> usize {
-        let sample_size = 1000.min(self.table.len());
+        let sample_clusters = (1000 / CLUSTER_ENTRIES).min(self.table.len());
+        let generation = self.generation();
         let mut filled = 0;
 
-        for i in 0..sample_size {
-            let entry = unsafe { self.table.get_unchecked(i) };
-            let data = entry.data;
-            let signature = entry.signature;
+        for entry in self.table[..sample_clusters]
+            .iter()
+            .flat_map(|cluster| &cluster.entries)
+        {
+            let (data, signature) = entry.load();
 
-            if (data ^ signature) != 0 {
+            if (data ^ signature) != 0 && unpack_age(data) == generation {
                 filled += 1;
             }
         }
 
-        (filled * 1000) / sample_size
+        (filled * 1000) / (sample_clusters * CLUSTER_ENTRIES)
     }
 }
+
 /// Exact score stored in the transposition table.
 pub const EXACT: u8 = 0;
 /// Lower-bound score stored in the transposition table.
 pub const LOWER_BOUND: u8 = 1;
 /// Upper-bound score stored in the transposition table.
 pub const UPPER_BOUND: u8 = 2;
+
+#[cfg(test)]
+mod tests {
+    use super::{TranspositionTable, EXACT, LOWER_BOUND, UPPER_BOUND};
+    use crate::{Move, MoveType};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn stores_and_probes_entries() {
+        let tt = TranspositionTable::new_mb(1);
+        let hash = 0x1234_5678_9ABC_DEF0;
+        let mv = Move::new(12, 28, MoveType::DoublePush);
+
+        tt.store(hash, mv, -321, Some(45), 7, LOWER_BOUND, true);
+        let entry = tt.probe(hash).expect("entry should be stored");
+
+        assert_eq!(entry.best_move, mv);
+        assert_eq!(entry.score, -321);
+        assert_eq!(entry.static_eval, Some(45));
+        assert_eq!(entry.depth, 7);
+        assert_eq!(entry.flag, LOWER_BOUND);
+        assert!(entry.is_pv);
+        assert!(tt.probe(hash ^ 1).is_none());
+
+        // a refresh without a move or eval keeps the ones already stored.
+        tt.store(hash, Move(0), -48_990, None, 9, UPPER_BOUND, false);
+        let entry = tt.probe(hash).expect("entry should be refreshed");
+
+        assert_eq!(entry.best_move, mv);
+        assert_eq!(entry.score, -48_990);
+        assert_eq!(entry.static_eval, Some(45));
+        assert_eq!(entry.flag, UPPER_BOUND);
+        assert!(entry.is_pv);
+
+        // depth-0 results from quiescence search leave the deeper entry alone, exact or not.
+        tt.store(hash, mv, 12, Some(12), 0, LOWER_BOUND, false);
+        assert_eq!(tt.probe(hash).map(|entry| entry.depth), Some(9));
+        tt.store(hash, mv, 12, Some(12), 0, EXACT, false);
+        let entry = tt.probe(hash).expect("deep entry should survive");
+        assert_eq!((entry.depth, entry.flag), (9, UPPER_BOUND));
+
+        tt.clear();
+        assert!(tt.probe(hash).is_none());
+    }
+
+    #[test]
+    fn full_cluster_evicts_shallow_entries_first() {
+        let tt = TranspositionTable::new_mb(1);
+        // identical low bits map every hash to the same cluster.
+        let colliding = |k: u64| 0x0000_0000_0000_0042 | (k << 40);
+        let mv = Move::new(12, 28, MoveType::DoublePush);
+
+        tt.store(colliding(1), mv, 10, None, 20, EXACT, false);
+        for k in 2..8 {
+            tt.store(colliding(k), mv, 0, None, 1, LOWER_BOUND, false);
+        }
+
+        let deep = tt.probe(colliding(1)).expect("deep entry should survive");
+        assert_eq!(deep.depth, 20);
+
+        // a deep entry outlives one search but eventually ages out for fresh ones.
+        tt.new_search();
+        assert_eq!(tt.hashfull(), 0);
+        for k in 8..12 {
+            tt.store(colliding(k), mv, 0, None, 4, LOWER_BOUND, false);
+        }
+        assert!(tt.probe(colliding(1)).is_some());
+
+        for _ in 0..5 {
+            tt.new_search();
+        }
+        for k in 12..16 {
+            tt.store(colliding(k), mv, 0, None, 4, LOWER_BOUND, false);
+        }
+        assert!(tt.probe(colliding(1)).is_none());
+    }
+
+    #[test]
+    fn concurrent_writers_never_expose_torn_entries() {
+        let tt = Arc::new(TranspositionTable::new_mb(1));
+
+        // every writer derives the payload from the hash, so any mismatch on probe is a torn read.
+        let payload = |hash: u64| {
+            let score = (hash % 20_000) as i32 - 10_000;
+            let depth = (hash % 64) as u8;
+            (Move((hash >> 16) as u16), score, depth)
+        };
+
+        let handles: Vec<_> = (0..4u64)
+            .map(|thread_id| {
+                let tt = Arc::clone(&tt);
+                thread::spawn(move || {
+                    let mut state = 0x9E37_79B9_7F4A_7C15u64 ^ thread_id;
+                    for _ in 0..200_000 {
+                        state ^= state << 13;
+                        state ^= state >> 7;
+                        state ^= state << 17;
+                        let hash = state & 0xFFFF_FFFF_0000_3FFF;
+
+                        let (mv, score, depth) = payload(hash);
+                        tt.store(hash, mv, score, Some(-score), depth, EXACT, false);
+
+                        if let Some(entry) = tt.probe(hash) {
+                            let (mv, score, depth) = payload(hash);
+                            assert_eq!(entry.best_move, mv);
+                            assert_eq!(entry.score, score);
+                            assert_eq!(entry.static_eval, Some(-score));
+                            assert_eq!(entry.depth, depth);
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().expect("writer thread panicked");
+        }
+
+        assert!(tt.hashfull() > 0);
+    }
+}
@@ -112,6 +112,18 @@ impl CastleRights {
     const BLACK_KING: u8 = 4;
     const BLACK_QUEEN: u8 = 8;
 
+    #[inline(always)]
+    /// Returns the right bit for `color` castling on the given side.
+    pub const fn right(color: Color, kingside: bool) -> u8 {
+        1 << Self::index(color, kingside)
+    }
+
+    #[inline(always)]
+    /// Returns the 0..4 slot of a right, in `K`, `Q`, `k`, `q` order.
+    pub const fn index(color: Color, kingside: bool) -> usize {
+        (color as usize) * 2 + if kingside { 0 } else { 1 }
+    }
+
     #[inline(always)]
     /// Returns `true` if `color` can castle kingside.
     pub const fn can_castle_kingside(self, color: Color) -> bool {
@@ -140,15 +152,9 @@ impl CastleRights {
     }
 
     #[inline(always)]
-    /// Removes castling rights affected by a rook move or capture on `sq`.
-    pub fn remove_rook(&mut self, sq: usize) {
-        match sq {
-            0 => self.0 &= !Self::WHITE_QUEEN,
-            7 => self.0 &= !Self::WHITE_KING,
-            56 => self.0 &= !Self::BLACK_QUEEN,
-            63 => self.0 &= !Self::BLACK_KING,
-            _ => {}
-        }
+    /// Removes every right whose bit is set in `mask`.
+    pub fn remove(&mut self, mask: u8) {
+        self.0 &= !mask;
     }
 }
 
@@ -231,11 +237,23 @@ impl Move {
     }
 
     const fn promotion_suffix(self) -> Option<char> {
+        match self.promotion_piece() {
+            Some(Piece::Queen) => Some('q'),
+            Some(Piece::Rook) => Some('r'),
+            Some(Piece::Bishop) => Some('b'),
+            Some(Piece::Knight) => Some('n'),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    /// Returns the piece a promotion turns into, or `None` for other moves.
+    pub const fn promotion_piece(self) -> Option<Piece> {
         match self.move_type() {
-            MoveType::PromotionQueen | MoveType::CapturePromotionQueen => Some('q'),
-            MoveType::PromotionRook | MoveType::CapturePromotionRook => Some('r'),
-            MoveType::PromotionBishop | MoveType::CapturePromotionBishop => Some('b'),
-            MoveType::PromotionKnight | MoveType::CapturePromotionKnight => Some('n'),
+            MoveType::PromotionQueen | MoveType::CapturePromotionQueen => Some(Piece::Queen),
+            MoveType::PromotionRook | MoveType::CapturePromotionRook => Some(Piece::Rook),
+            MoveType::PromotionBishop | MoveType::CapturePromotionBishop => Some(Piece::Bishop),
+            MoveType::PromotionKnight | MoveType::CapturePromotionKnight => Some(Piece::Knight),
             _ => None,
         }
     }
@@ -1,18 +1,26 @@
-use crate::{eval::EvalProvider, tpt::TranspositionTable, Position};
+use crate::{
+    eval::EvalProvider,
+    search::{PonderSignal, SearchOptions},
+    tpt::TranspositionTable,
+    Position,
+};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::thread::JoinHandle;
 
 pub(crate) struct ActiveSearch {
     pub(crate) stop_signal: Arc<AtomicBool>,
-    pub(crate) handle: JoinHandle<TranspositionTable>,
+    pub(crate) ponder: Option<Arc<PonderSignal>>,
+    pub(crate) handle: JoinHandle<()>,
 }
 
 /// UCI-facing engine state.
 pub struct UciEngine<E: EvalProvider> {
     pub(crate) position: Position,
-    pub(crate) tt: Option<TranspositionTable>,
+    pub(crate) tt: Arc<TranspositionTable>,
     pub(crate) eval: E,
+    pub(crate) options: SearchOptions,
+    pub(crate) chess960: bool,
     pub(crate) active_search: Option<ActiveSearch>,
 }
 
@@ -21,8 +29,10 @@ impl<E: EvalProvider> UciEngine<E> {
     pub fn new(eval: E) -> Self {
         UciEngine {
             position: Position::new(),
-            tt: Some(TranspositionTable::new_mb(256)),
+            tt: Arc::new(TranspositionTable::new_mb(256)),
             eval,
+            options: SearchOptions::new(),
+            chess960: false,
             active_search: None,
         }
     }
@@ -1,73 +1,75 @@
 use super::UciEngine;
-use crate::{Move, Position};
+use crate::{Move, MoveCollector, MoveType, Position};
 
 impl<E: crate::eval::EvalProvider> UciEngine<E> {
+    /// Resolves a long-algebraic move such as `e2e4` or `a7a8q` to a legal move.
+    ///
+    /// Returns `None` for malformed strings and for moves that are not legal in
+    /// `pos`, so the caller can reject them before anything is played. Castling
+    /// is accepted both as the king's two-square step and as king-takes-rook,
+    /// whichever mode the position is in.
     pub(crate) fn parse_move(move_str: &str, pos: &Position) -> Option<Move> {
-        if move_str.len() < 4 {
-            return None;
-        }
+        let move_str = move_str.to_ascii_lowercase();
 
-        let from = Self::parse_square(&move_str[0..2])?;
-        let to = Self::parse_square(&move_str[2..4])?;
+        let mut collector = MoveCollector::new();
+        pos.generate_moves(&mut collector);
+        let legal = collector.as_slice();
 
-        let (piece, _color) = pos.piece_at(from)?;
-
-        use crate::types::{MoveType, Piece};
-
-        let is_capture = pos.piece_at(to).is_some();
-
-        if move_str.len() == 5 {
-            let promo = move_str.chars().nth(4)?;
-            let move_type = match (promo, is_capture) {
-                ('q', false) => MoveType::PromotionQueen,
-                ('r', false) => MoveType::PromotionRook,
-                ('b', false) => MoveType::PromotionBishop,
-                ('n', false) => MoveType::PromotionKnight,
-                ('q', true) => MoveType::CapturePromotionQueen,
-                ('r', true) => MoveType::CapturePromotionRook,
-                ('b', true) => MoveType::CapturePromotionBishop,
-                ('n', true) => MoveType::CapturePromotionKnight,
-                _ => return None,
-            };
-            return Some(Move::new(from, to, move_type));
-        }
-
-        if piece == Piece::King && ((from as i32 - to as i32).abs() == 2) {
-            return Some(Move::new(from, to, MoveType::Castle));
-        }
+        legal
+            .iter()
+            .copied()
+            .find(|&mv| pos.move_to_uci(mv) == move_str)
+            .or_else(|| {
+                legal.iter().copied().find(|&mv| {
+                    mv.move_type() == MoveType::Castle && castling_notations(pos, mv, &move_str)
+                })
+            })
+    }
+}
 
-        if piece == Piece::Pawn {
-            if let Some(ep_sq) = pos.en_passant {
-                if to == ep_sq as usize && !is_capture {
-                    return Some(Move::new(from, to, MoveType::EnPassant));
-                }
-            }
-            if (from as i32 - to as i32).abs() == 16 {
-                return Some(Move::new(from, to, MoveType::DoublePush));
-            }
-        }
+/// Matches either castling notation, whichever the position does not print.
+fn castling_notations(pos: &Position, mv: Move, move_str: &str) -> bool {
+    let (rook_from, _) = pos.castling_rook_move(mv.to());
+    mv.to_uci() == move_str
+        || Move::new(mv.from(), rook_from, MoveType::Castle).to_uci() == move_str
+}
 
-        let move_type = if is_capture {
-            MoveType::Capture
-        } else {
-            MoveType::Quiet
-        };
+#[cfg(test)]
+mod tests {
+    use crate::{uci::UciEngine, PestoProvider, Position};
 
-        Some(Move::new(from, to, move_type))
+    fn parse(fen: &str, uci: &str) -> Option<String> {
+        let pos = Position::from_fen(fen).expect("valid FEN");
+        UciEngine::<PestoProvider>::parse_move(uci, &pos).map(|mv| pos.move_to_uci(mv))
     }
 
-    pub(crate) fn parse_square(s: &str) -> Option<usize> {
-        if s.len() != 2 {
-            return None;
-        }
-
-        let file = (s.as_bytes()[0] as i32 - b'a' as i32) as usize;
-        let rank = (s.as_bytes()[1] as i32 - b'1' as i32) as usize;
+    #[test]
+    fn resolves_only_legal_moves() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!(parse(start, "e2e4"), Some("e2e4".to_string()));
+        assert_eq!(parse(start, "g1f3"), Some("g1f3".to_string()));
+        assert_eq!(parse(start, "e2e5"), None, "pawn cannot jump three squares");
+        assert_eq!(parse(start, "e7e5"), None, "not the side to move");
+        assert_eq!(parse(start, "e3e4"), None, "empty origin square");
+        assert_eq!(parse(start, "e1g1"), None, "castling path is blocked");
+        assert_eq!(parse(start, "e2"), None);
+        assert_eq!(parse(start, "z9z9"), None);
+        assert_eq!(parse(start, "e2e4q"), None, "not a promotion");
+    }
 
-        if file > 7 || rank > 7 {
-            return None;
-        }
+    #[test]
+    fn resolves_promotions_en_passant_and_castling() {
+        let fen = "r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1";
+        assert_eq!(parse(fen, "b7b8n"), Some("b7b8n".to_string()));
+        assert_eq!(parse(fen, "b7a8Q"), Some("b7a8q".to_string()));
+        assert_eq!(parse(fen, "b7b8"), None, "promotion piece is required");
+        assert_eq!(parse(fen, "e5d6"), Some("e5d6".to_string()));
+        assert_eq!(parse(fen, "e1g1"), Some("e1g1".to_string()));
+        assert_eq!(parse(fen, "e1h1"), Some("e1g1".to_string()));
 
-        Some(rank * 8 + file)
+        let chess960 = "4k3/8/8/8/8/8/8/1R4KR w BH - 0 1";
+        assert_eq!(parse(chess960, "g1h1"), Some("g1h1".to_string()));
+        assert_eq!(parse(chess960, "g1b1"), Some("g1b1".to_string()));
+        assert_eq!(parse(chess960, "g1c1"), Some("g1b1".to_string()));
     }
 }
@@ -1,6 +1,7 @@
 use super::UciEngine;
 use crate::{
-    search::{search_with_stop_signal, SearchLimits},
+    movegen::PerftTable,
+    search::{search_with_stop_signal, PonderSignal, SearchLimits},
     time_control::{calculate_time_allocation, clamp_movetime_budget, clamp_search_budget},
     Position,
 };
@@ -10,6 +11,24 @@ use std::sync::Arc;
 use std::thread;
 
 const UCI_SEARCH_STACK_SIZE: usize = 32 * 1024 * 1024;
+const MAX_MULTI_PV: usize = 256;
+const MAX_THREADS: usize = 256;
+const PERFT_HASH_MB: usize = 16;
+/// Tokens that end a `go searchmoves` move list.
+const GO_KEYWORDS: [&str; 12] = [
+    "searchmoves",
+    "ponder",
+    "wtime",
+    "btime",
+    "winc",
+    "binc",
+    "movestogo",
+    "depth",
+    "nodes",
+    "mate",
+    "movetime",
+    "infinite",
+];
 
 impl<E: crate::eval::EvalProvider> UciEngine<E> {
     /// Starts the blocking UCI command loop on standard input and output.
@@ -45,6 +64,8 @@ impl<E: crate::eval::EvalProvider> UciEngine<E> {
                     break;
                 }
                 "stop" => self.signal_stop(),
+                "ponderhit" => self.handle_ponderhit(),
+                "hashfull" => self.handle_hashfull(),
                 _ => {}
             }
         }
@@ -56,6 +77,16 @@ impl<E: crate::eval::EvalProvider> UciEngine<E> {
         println!("id name OopsMate");
         println!("id author Swoyam P.");
         println!("option name Hash type spin default 64 min 1 max 1024");
+        println!("option name Ponder type check default false");
+        println!("option name UCI_Chess960 type check default false");
+        println!(
+            "option name MultiPV type spin default 1 min 1 max {}",
+            MAX_MULTI_PV
+        );
+        println!(
+            "option name Threads type spin default 1 min 1 max {}",
+            MAX_THREADS
+        );
         println!("uciok");
         let _ = std::io::stdout().flush();
     }
@@ -78,19 +109,38 @@ impl<E: crate::eval::EvalProvider> UciEngine<E> {
 
         let value = parts[name_end + 1];
 
-        if name.as_str() == "hash" {
-            if let Ok(mb) = value.parse::<usize>() {
-                self.tt = Some(crate::tpt::TranspositionTable::new_mb(mb));
+        match name.as_str() {
+            "hash" => {
+                if let Ok(mb) = value.parse::<usize>() {
+                    // searches hold their own handle, so resizing waits for them to finish.
+                    self.stop_search_and_wait();
+                    self.tt = Arc::new(crate::tpt::TranspositionTable::new_mb(mb));
+                }
+            }
+            "multipv" => {
+                if let Ok(lines) = value.parse::<usize>() {
+                    self.options.multi_pv = lines.clamp(1, MAX_MULTI_PV);
+                }
+            }
+            "threads" => {
+                if let Ok(threads) = value.parse::<usize>() {
+                    self.options.threads = threads.clamp(1, MAX_THREADS);
+                }
+            }
+            "uci_chess960" => {
+                if let Ok(chess960) = value.parse::<bool>() {
+                    self.chess960 = chess960;
+                    self.position.set_chess960(chess960);
+                }
             }
+            _ => {}
         }
     }
 
     fn handle_new_game(&mut self) {
         self.stop_search_and_wait();
         self.position = Position::new();
-        if let Some(tt) = &mut self.tt {
-            tt.clear();
-        }
+        self.tt.clear();
     }
 
     fn handle_position(&mut self, parts: &[&str]) {
@@ -100,11 +150,9 @@ impl<E: crate::eval::EvalProvider> UciEngine<E> {
             return;
         }
 
-        let mut moves_idx = None;
-
-        if parts[0] == "startpos" {
-            self.position = Position::new();
-            moves_idx = parts.iter().position(|&s| s == "moves");
+        // the command is applied to a copy, so a bad fen or move leaves the current position intact.
+        let mut pos = if parts[0] == "startpos" {
+            Position::new()
         } else if parts[0] == "fen" {
             let fen_parts: Vec<&str> = parts
                 .iter()
@@ -113,34 +161,50 @@ impl<E: crate::eval::EvalProvider> UciEngine<E> {
                 .copied()
                 .collect();
 
-            let fen = fen_parts.join(" ");
-
-            match Position::from_fen(&fen) {
-                Ok(pos) => self.position = pos,
+            match Position::from_fen(&fen_parts.join(" ")) {
+                Ok(pos) => pos,
                 Err(e) => {
-                    eprintln!("Invalid FEN: {}", e);
+                    println!("info string invalid fen: {}", e);
+                    let _ = std::io::stdout().flush();
                     return;
                 }
             }
+        } else {
+            return;
+        };
 
-            moves_idx = parts.iter().position(|&s| s == "moves");
+        // chess960: a classical start still needs king-takes-rook castling notation.
+        if self.chess960 {
+            pos.set_chess960(true);
         }
 
-        if let Some(idx) = moves_idx {
+        if let Some(idx) = parts.iter().position(|&s| s == "moves") {
             for move_str in &parts[idx + 1..] {
-                if let Some(m) = Self::parse_move(move_str, &self.position) {
-                    self.position.make_move(m);
-                } else {
-                    eprintln!("Invalid move format: {}", move_str);
-                    break;
+                match Self::parse_move(move_str, &pos) {
+                    Some(m) => pos.make_move(m),
+                    None => {
+                        println!("info string illegal move: {}", move_str);
+                        let _ = std::io::stdout().flush();
+                        return;
+                    }
                 }
             }
         }
+
+        self.position = pos;
     }
 
     fn handle_go(&mut self, parts: &[&str]) {
         self.stop_search_and_wait();
 
+        // perft: `go perft N` prints a divide instead of starting a search.
+        if parts.first() == Some(&"perft") {
+            if let Some(depth) = parts.get(1).and_then(|d| d.parse::<u8>().ok()) {
+                self.handle_perft(depth);
+            }
+            return;
+        }
+
         let mut wtime = None;
         let mut btime = None;
         let mut winc = 0;
@@ -148,7 +212,11 @@ impl<E: crate::eval::EvalProvider> UciEngine<E> {
         let mut movestogo = None;
         let mut depth = 50;
         let mut infinite = false;
+        let mut ponder = false;
         let mut movetime = None;
+        let mut nodes = None;
+        let mut mate = None;
+        let mut search_moves = Vec::new();
 
         let mut i = 0;
         while i < parts.len() {
@@ -213,6 +281,36 @@ impl<E: crate::eval::EvalProvider> UciEngine<E> {
                     infinite = true;
                     i += 1;
                 }
+                "ponder" => {
+                    ponder = true;
+                    i += 1;
+                }
+                "nodes" => {
+                    if i + 1 < parts.len() {
+                        nodes = parts[i + 1].parse().ok();
+                        i += 2;
+                    } else {
+                        i += 1;
+                    }
+                }
+                "mate" => {
+                    if i + 1 < parts.len() {
+                        mate = parts[i + 1].parse().ok();
+                        i += 2;
+                    } else {
+                        i += 1;
+                    }
+                }
+                "searchmoves" => {
+                    i += 1;
+                    while i < parts.len() && !GO_KEYWORDS.contains(&parts[i]) {
+                        match Self::parse_move(parts[i], &self.position) {
+                            Some(m) => search_moves.push(m),
+                            None => println!("info string illegal move: {}", parts[i]),
+                        }
+                        i += 1;
+                    }
+                }
                 _ => i += 1,
             }
         }
@@ -236,47 +334,115 @@ impl<E: crate::eval::EvalProvider> UciEngine<E> {
         } else {
             SearchLimits::infinite()
         };
+        let limits = match nodes {
+            Some(nodes) => limits.with_nodes(nodes),
+            None => limits,
+        };
+        let limits = match mate {
+            Some(moves) => limits.with_mate(moves),
+            None => limits,
+        };
 
         let pos = self.position.clone();
-        let mut tt = self.tt.take().expect("transposition table missing");
+        let tt = Arc::clone(&self.tt);
         let eval = self.eval.clone();
+        let options = self.options.clone().with_search_moves(search_moves);
         let stop_signal = Arc::new(AtomicBool::new(false));
         let worker_signal = stop_signal.clone();
+        // ponder: the clock limits above only start once the GUI sends ponderhit.
+        let ponder = ponder.then(|| Arc::new(PonderSignal::new()));
+        let worker_ponder = ponder.clone();
 
         let handle = thread::Builder::new()
             .stack_size(UCI_SEARCH_STACK_SIZE)
             .spawn(move || {
-                let result =
-                    search_with_stop_signal(&pos, depth, limits, &mut tt, worker_signal, eval);
+                let result = search_with_stop_signal(
+                    &pos,
+                    depth,
+                    limits,
+                    &options,
+                    &tt,
+                    worker_signal,
+                    worker_ponder.clone(),
+                    eval,
+                );
+
+                // a search that finishes while pondering must hold its bestmove until ponderhit or stop.
+                if let Some(ponder) = &worker_ponder {
+                    ponder.wait_for_release();
+                }
 
                 if let Some(info) = result {
-                    println!("bestmove {}", info.best_move.to_uci());
+                    match info.pv.get(1) {
+                        Some(reply) => println!(
+                            "bestmove {} ponder {}",
+                            pos.move_to_uci(info.best_move),
+                            pos.move_to_uci(*reply)
+                        ),
+                        None => println!("bestmove {}", pos.move_to_uci(info.best_move)),
+                    }
                 } else {
                     println!("bestmove 0000");
                 }
 
                 let _ = std::io::stdout().flush();
-
-                tt
             })
             .expect("failed to spawn UCI search thread");
 
         self.active_search = Some(super::engine::ActiveSearch {
             stop_signal,
+            ponder,
             handle,
         });
     }
 
+    /// Prints the node count below each root move, then the total.
+    fn handle_perft(&mut self, depth: u8) {
+        let pos = &self.position;
+        let table = PerftTable::new_mb(PERFT_HASH_MB);
+        let divide = pos.perft_divide_bulk(depth, self.options.threads, Some(&table));
+
+        for &(mv, nodes) in &divide {
+            println!("{}: {}", pos.move_to_uci(mv), nodes);
+        }
+
+        let total: u64 = if depth == 0 {
+            1
+        } else {
+            divide.iter().map(|&(_, nodes)| nodes).sum()
+        };
+        println!();
+        println!("Nodes searched: {}", total);
+        let _ = std::io::stdout().flush();
+    }
+
+    /// Reports table occupancy; safe to call while a search is running.
+    fn handle_hashfull(&self) {
+        println!("info hashfull {}", self.tt.hashfull());
+        let _ = std::io::stdout().flush();
+    }
+
+    /// Switches a pondering search onto its normal clock limits.
+    fn handle_ponderhit(&self) {
+        if let Some(ponder) = self.active_search.as_ref().and_then(|a| a.ponder.as_ref()) {
+            ponder.ponderhit();
+        }
+    }
+
     fn signal_stop(&self) {
         if let Some(active) = &self.active_search {
             active.stop_signal.store(true, Ordering::Relaxed);
+            // releasing the ponder signal lets a finished ponder search report its bestmove.
+            if let Some(ponder) = &active.ponder {
+                ponder.ponderhit();
+            }
         }
     }
 
     fn stop_search_and_wait(&mut self) {
+        self.signal_stop();
         if let Some(active) = self.active_search.take() {
-            active.stop_signal.store(true, Ordering::Relaxed);
-            self.tt = Some(active.handle.join().expect("search thread panicked"));
+            active.handle.join().expect("search thread panicked");
         }
     }
 
@@ -288,7 +454,7 @@ impl<E: crate::eval::EvalProvider> UciEngine<E> {
 
         if is_finished {
             if let Some(active) = self.active_search.take() {
-                self.tt = Some(active.handle.join().expect("search thread panicked"));
+                active.handle.join().expect("search thread panicked");
             }
         }
     }